@@ -0,0 +1,280 @@
+use bevy_ecs::prelude::*;
+use bevy_math::{Rect, Vec2, Vec3, Vec3Swizzles};
+use bevy_reflect::prelude::*;
+use bevy_time::Time;
+
+use super::{CameraController, ControlMessage};
+use crate::LookTransform;
+
+/// Keeps the [`LookTransform::target`] of a [`CameraController`] camera inside a region of the XZ plane.
+///
+/// Panning and zooming towards the pointer clamp the target to the region. With a `soft_margin` the
+/// target can be dragged a bit outside the region, with increasing resistance, and springs back once
+/// the camera is no longer being panned.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Debug)]
+pub struct PanBounds {
+    pub shape: BoundsShape,
+    /// How far outside the region, in world units, the target can be dragged. `0.0` is a hard edge.
+    pub soft_margin: f32,
+    /// How fast the target springs back inside the region, the distance outside decays with
+    /// `exp(-spring_stiffness * seconds)`
+    pub spring_stiffness: f32,
+}
+
+/// A region of the XZ plane, the `x` of the points is the world X axis and the `y` is the world Z axis.
+#[derive(Clone, Debug, Reflect)]
+pub enum BoundsShape {
+    Rect(Rect),
+    /// The corners of a simple polygon, the last corner connects back to the first.
+    Polygon(Vec<Vec2>),
+}
+
+impl PanBounds {
+    /// Hard bounds with no soft margin
+    pub fn new(shape: BoundsShape) -> Self {
+        Self {
+            shape,
+            soft_margin: 0.0,
+            spring_stiffness: 10.0,
+        }
+    }
+
+    pub fn with_soft_margin(mut self, soft_margin: f32) -> Self {
+        self.soft_margin = soft_margin;
+        self
+    }
+
+    pub fn with_spring_stiffness(mut self, spring_stiffness: f32) -> Self {
+        self.spring_stiffness = spring_stiffness;
+        self
+    }
+
+    /// Returns `target` moved into the region, or into the soft margin around it.
+    ///
+    /// Inside the soft margin the distance outside the region is compressed so that the target
+    /// slows down the further out it is dragged, without ever leaving the margin.
+    pub fn constrain(&self, target: Vec3) -> Vec3 {
+        let point = target.xz();
+        let closest = self.shape.closest_point(point);
+        let outside = point - closest;
+
+        let constrained = if self.soft_margin > 0.0 && outside != Vec2::ZERO {
+            let distance = outside.length();
+            let resisted = self.soft_margin * (1.0 - (-distance / self.soft_margin).exp());
+            closest + outside * (resisted / distance)
+        } else {
+            closest
+        };
+
+        Vec3::new(constrained.x, target.y, constrained.y)
+    }
+
+    /// Returns the target that [`Self::constrain`] moves to `constrained`, the inverse of the soft margin.
+    ///
+    /// A `constrained` target past the soft margin has no such target, the furthest one is returned.
+    pub fn unconstrain(&self, constrained: Vec3) -> Vec3 {
+        let point = constrained.xz();
+        let closest = self.shape.closest_point(point);
+        let outside = point - closest;
+
+        if self.soft_margin <= 0.0 || outside == Vec2::ZERO {
+            return constrained;
+        }
+
+        let resisted = outside.length();
+        let distance =
+            -self.soft_margin * (1.0 - (resisted / self.soft_margin).min(MAX_RESISTANCE)).ln();
+        let unconstrained = closest + outside * (distance / resisted);
+
+        Vec3::new(unconstrained.x, constrained.y, unconstrained.y)
+    }
+
+    /// Returns `target`, which is already constrained, moved by `delta` and constrained again.
+    ///
+    /// The move is made before the soft margin resists it, so moving by zero keeps the target in
+    /// place and moving back and forth returns it to where it started.
+    pub fn constrain_translation(&self, target: Vec3, delta: Vec3) -> Vec3 {
+        self.constrain(self.unconstrain(target) + delta)
+    }
+}
+
+/// How much of the soft margin [`PanBounds::unconstrain`] can undo, all of it is infinitely far away
+const MAX_RESISTANCE: f32 = 0.999;
+
+impl BoundsShape {
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            BoundsShape::Rect(rect) => rect.contains(point),
+            BoundsShape::Polygon(corners) => {
+                // Even-odd rule, count the edges crossed by a ray going towards positive x
+                let mut inside = false;
+                for (a, b) in edges(corners) {
+                    if (a.y > point.y) != (b.y > point.y) {
+                        let crossing_x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                        if point.x < crossing_x {
+                            inside = !inside;
+                        }
+                    }
+                }
+                inside
+            }
+        }
+    }
+
+    /// Returns the point in the region that is closest to `point`, which is `point` itself if it's inside.
+    pub fn closest_point(&self, point: Vec2) -> Vec2 {
+        match self {
+            BoundsShape::Rect(rect) => point.clamp(rect.min, rect.max),
+            BoundsShape::Polygon(_) if self.contains(point) => point,
+            BoundsShape::Polygon(corners) => edges(corners)
+                .map(|(a, b)| {
+                    let edge = b - a;
+                    let t = (point - a).dot(edge) / edge.length_squared().max(f32::EPSILON);
+                    a + edge * t.clamp(0.0, 1.0)
+                })
+                .min_by(|a, b| {
+                    a.distance_squared(point)
+                        .total_cmp(&b.distance_squared(point))
+                })
+                .unwrap_or(point),
+        }
+    }
+}
+
+fn edges(corners: &[Vec2]) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+    corners
+        .iter()
+        .copied()
+        .zip(corners.iter().copied().cycle().skip(1))
+}
+
+/// Eases targets that are outside their [`PanBounds`] back inside, unless the camera is being panned
+pub(super) fn spring_back(
+    mut events: MessageReader<ControlMessage>,
+    mut cameras: Query<(Entity, &mut LookTransform, &CameraController, &PanBounds)>,
+    time: Res<Time>,
+) {
+    let panned_cameras = events
+        .read()
+        .filter_map(|event| match event {
+            ControlMessage::TranslateTarget(camera, _) => Some(*camera),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    for (camera, mut transform, controller, bounds) in cameras.iter_mut() {
+        if !controller.enabled || panned_cameras.contains(&camera) {
+            continue;
+        }
+
+        let target = transform.target.xz();
+        let closest = bounds.shape.closest_point(target);
+        if closest == target {
+            continue;
+        }
+
+        let decay = (-bounds.spring_stiffness * time.delta_secs()).exp();
+        let mut new_target = closest + (target - closest) * decay;
+        // Snap the last bit to stop changing the transform every frame
+        if new_target.distance_squared(closest) < 1e-6 {
+            new_target = closest;
+        }
+
+        let delta = Vec3::new(new_target.x - target.x, 0.0, new_target.y - target.y);
+        transform.target += delta;
+        transform.eye += delta;
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l_shape() -> BoundsShape {
+        BoundsShape::Polygon(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 5.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ])
+    }
+
+    #[test]
+    fn test_polygon_contains() {
+        let shape = l_shape();
+
+        assert!(shape.contains(Vec2::new(2.0, 8.0)));
+        assert!(shape.contains(Vec2::new(8.0, 2.0)));
+        assert!(!shape.contains(Vec2::new(8.0, 8.0)));
+        assert!(!shape.contains(Vec2::new(-1.0, 2.0)));
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let shape = l_shape();
+
+        assert_eq!(
+            shape.closest_point(Vec2::new(2.0, 8.0)),
+            Vec2::new(2.0, 8.0)
+        );
+        assert_eq!(
+            shape.closest_point(Vec2::new(9.0, 7.0)),
+            Vec2::new(9.0, 5.0)
+        );
+        assert_eq!(
+            shape.closest_point(Vec2::new(-3.0, 4.0)),
+            Vec2::new(0.0, 4.0)
+        );
+
+        let rect = BoundsShape::Rect(Rect::new(-1.0, -1.0, 1.0, 1.0));
+        assert_eq!(rect.closest_point(Vec2::new(3.0, 0.5)), Vec2::new(1.0, 0.5));
+    }
+
+    #[test]
+    fn test_soft_margin_resists() {
+        let bounds = PanBounds::new(BoundsShape::Rect(Rect::new(-1.0, -1.0, 1.0, 1.0)))
+            .with_soft_margin(2.0);
+
+        let near = bounds.constrain(Vec3::new(1.5, 3.0, 0.0));
+        let far = bounds.constrain(Vec3::new(100.0, 3.0, 0.0));
+
+        assert!(near.x > 1.0 && near.x < 1.5);
+        assert!(far.x > near.x && far.x <= 3.0);
+        assert_eq!(far.y, 3.0);
+
+        let hard = PanBounds::new(BoundsShape::Rect(Rect::new(-1.0, -1.0, 1.0, 1.0)));
+        assert_eq!(
+            hard.constrain(Vec3::new(100.0, 0.0, 0.0)),
+            Vec3::new(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_constraining_again_does_not_move() {
+        let bounds = PanBounds::new(l_shape()).with_soft_margin(2.0);
+
+        let start = Vec3::new(8.0, 1.0, 4.0);
+        let dragged = bounds.constrain_translation(start, Vec3::new(0.0, 0.0, 3.0));
+        let held = bounds.constrain_translation(dragged, Vec3::ZERO);
+        let held_again = bounds.constrain_translation(held, Vec3::ZERO);
+
+        // In the soft margin, short of where it was dragged to
+        assert!(dragged.z > 5.0 && dragged.z < 7.0);
+        assert!(held.abs_diff_eq(dragged, 1e-5));
+        assert!(held_again.abs_diff_eq(dragged, 1e-5));
+
+        // Dragging back undoes the resistance
+        let back = bounds.constrain_translation(held_again, Vec3::new(0.0, 0.0, -3.0));
+        assert!(back.abs_diff_eq(start, 1e-4));
+    }
+}
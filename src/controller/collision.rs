@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use bevy_ecs::{prelude::*, query::QueryData};
+use bevy_math::{Dir3, Ray3d, Vec3};
+use bevy_time::Time;
+use bevy_transform::components::Transform;
+
+use super::{CameraController, Ground, GroundQuery, ground::ground_point};
+use crate::{LookTransform, Smoother};
+
+/// The eye is back out once it is this close to the radius, in world units
+const RESTORED_DISTANCE: f32 = 0.001;
+
+/// Keeps the eye of a [`CameraController`] camera from going through the ground and obstacles.
+///
+/// When something is between the target and the eye, the eye is pushed along the look ray towards the
+/// target. Only the camera's `Transform` is moved, the [`LookTransform`] keeps its radius and the eye
+/// eases back out to it once the view clears.
+///
+/// The eye collides with the [`Ground`] of the camera, or its grab plane if it has none, and the
+/// `obstacles`.
+#[derive(Component, Clone)]
+pub struct CameraCollision {
+    /// Other things to collide with, like buildings. Only [`GroundQuery::intersect`] is used.
+    pub obstacles: Option<Arc<dyn GroundQuery>>,
+    /// How far from the ground and obstacles the eye stays, in world units
+    pub margin: f32,
+    /// How fast the eye moves back out when the view clears, the remaining distance decays with
+    /// `exp(-restore_speed * seconds)`
+    pub restore_speed: f32,
+    /// How far from the target the eye was last frame, when it was pushed in
+    distance: Option<f32>,
+}
+
+impl Default for CameraCollision {
+    fn default() -> Self {
+        Self {
+            obstacles: None,
+            margin: 0.5,
+            restore_speed: 5.0,
+            distance: None,
+        }
+    }
+}
+
+impl CameraCollision {
+    pub fn with_obstacles(mut self, obstacles: impl GroundQuery) -> Self {
+        self.obstacles = Some(Arc::new(obstacles));
+        self
+    }
+
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_restore_speed(mut self, restore_speed: f32) -> Self {
+        self.restore_speed = restore_speed;
+        self
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub(super) struct CollidingCamera {
+    transform: &'static mut Transform,
+    collision: &'static mut CameraCollision,
+    look_transform: &'static LookTransform,
+    controller: &'static CameraController,
+    smoother: Option<&'static Smoother>,
+    ground: Option<&'static Ground>,
+}
+
+/// Moves the eye in `Transform` towards the target when the ground or an obstacle is in the way
+pub(super) fn collide(mut cameras: Query<CollidingCamera>, time: Res<Time>) {
+    for CollidingCameraItem {
+        mut transform,
+        mut collision,
+        look_transform,
+        controller,
+        smoother,
+        ground,
+    } in cameras.iter_mut()
+    {
+        if !controller.enabled {
+            collision.distance = None;
+            continue;
+        }
+
+        // Use what the smoothing shows this frame, not where the camera is heading
+        let shown = smoother
+            .and_then(Smoother::smoothed)
+            .unwrap_or(*look_transform);
+        let Ok(direction) = Dir3::new(shown.eye - shown.target) else {
+            continue;
+        };
+        let radius = shown.radius();
+
+        // Start just above the target, which is on the ground
+        let origin = shown.target + Vec3::Y * collision.margin;
+        let ray = Ray3d::new(origin, direction);
+        let hit_distance = |point: Vec3| (point - shown.target).dot(*direction);
+
+        let ground_hit = ground_point(ray, controller, ground);
+        let obstacle_hit = collision
+            .obstacles
+            .as_ref()
+            .and_then(|obstacles| obstacles.intersect(ray));
+
+        let allowed = [ground_hit, obstacle_hit]
+            .into_iter()
+            .flatten()
+            .map(hit_distance)
+            .filter(|distance| *distance >= 0.0)
+            .map(|distance| (distance - collision.margin).max(0.0))
+            .fold(radius, f32::min);
+
+        // Pushed in at once, eased back out
+        let distance = match collision.distance {
+            Some(previous) if previous < allowed => {
+                let decay = (-collision.restore_speed * time.delta_secs()).exp();
+                allowed - (allowed - previous) * decay
+            }
+            _ => allowed,
+        };
+
+        collision.distance = (distance < radius - RESTORED_DISTANCE).then_some(distance);
+        if collision.distance.is_some() {
+            transform.translation = shown.target + direction * distance;
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_app::prelude::*;
+    use bevy_math::{UVec2, Vec2};
+    use core::time::Duration;
+
+    use crate::{
+        controller::{FlatGround, Heightmap},
+        test_utils::headless_app,
+    };
+
+    #[test]
+    fn test_eye_is_pushed_in_and_restored() {
+        let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+        app.add_systems(PreUpdate, (crate::look_transform_system, collide).chain());
+
+        // A ridge two units high between the target and the eye
+        let ridge = Heightmap::new(
+            (0..11)
+                .flat_map(|z| (0..3).map(move |_| if z == 5 { 2.0 } else { 0.0 }))
+                .collect(),
+            UVec2::new(3, 11),
+            Vec2::new(-1.0, 0.0),
+            1.0,
+        );
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 2.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((
+                look_transform,
+                CameraController {
+                    smoothing_weight: 0.0,
+                    ..Default::default()
+                },
+                Ground::new(ridge),
+                CameraCollision::default().with_margin(0.1),
+            ))
+            .id();
+
+        let eye = |app: &App| app.world().get::<Transform>(camera).unwrap().translation;
+
+        app.update();
+        app.update();
+
+        // In front of the ridge, looking in the same direction
+        assert!(eye(&app).z < 5.0);
+        assert!(eye(&app).z > 0.0);
+        let look_direction = app.world().get::<Transform>(camera).unwrap().forward();
+        assert!(
+            look_direction
+                .dot(look_transform.look_direction().unwrap())
+                .abs()
+                > 0.999
+        );
+        assert_eq!(
+            *app.world().get::<LookTransform>(camera).unwrap(),
+            look_transform
+        );
+
+        // Clear the view
+        app.world_mut()
+            .entity_mut(camera)
+            .insert(Ground::new(FlatGround { height: 0.0 }));
+        app.update();
+        let eased_eye = eye(&app);
+        assert!(eased_eye.z < look_transform.eye.z - 0.1);
+
+        for _ in 0..120 {
+            app.update();
+        }
+        assert!(eye(&app).abs_diff_eq(look_transform.eye, 1e-2));
+        assert!(
+            app.world()
+                .get::<CameraCollision>(camera)
+                .unwrap()
+                .distance
+                .is_none()
+        );
+    }
+}
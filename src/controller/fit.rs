@@ -0,0 +1,378 @@
+use bevy_camera::{Projection, primitives::Aabb};
+use bevy_ecs::prelude::*;
+use bevy_math::{Vec3, Vec3A};
+use bevy_transform::components::GlobalTransform;
+
+use super::{
+    CameraController, ControllerSettings, FlyTo, FlyToFinished, ZoomAnimation, fly_to::Flight,
+};
+use crate::LookTransform;
+
+/// What to fit in the view with [`FitToBounds`]
+#[derive(Debug, Clone)]
+pub enum FitBounds {
+    Aabb(Aabb),
+    Points(Vec<Vec3>),
+    /// The positions of the entities from their [`GlobalTransform`], or all of their [`Aabb`] if they
+    /// have one
+    Entities(Vec<Entity>),
+}
+
+/// Moves and zooms a [`CameraController`] camera so that the bounds fit in its view, keeping the
+/// current yaw and pitch.
+///
+/// The zoom is limited by the [`CameraControllerSettings`](super::CameraControllerSettings) of the camera.
+#[derive(Message, Debug, Clone)]
+pub struct FitToBounds {
+    pub camera: Entity,
+    pub bounds: FitBounds,
+    /// The fraction of the view to keep empty on each side, in the range `0.0..0.5`
+    pub padding: f32,
+    /// Flies the camera there with a [`FlyTo`] taking this many seconds, `None` moves it there at once
+    pub duration: Option<f32>,
+}
+
+impl FitToBounds {
+    pub fn new(camera: Entity, bounds: FitBounds) -> Self {
+        Self {
+            camera,
+            bounds,
+            padding: 0.1,
+            duration: Some(1.0),
+        }
+    }
+
+    pub fn with_padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Option<f32>) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// Where a camera fits the bounds in its view
+#[derive(Debug, Clone, Copy)]
+struct Fit {
+    target: Vec3,
+    radius: f32,
+    orthographic_scale: Option<f32>,
+}
+
+/// Finds the target on the grab plane and the radius, or orthographic scale, that fits all `points` in
+/// the view of a camera looking the same way as `look_transform`.
+///
+/// The center of the points ends up in the center of the view.
+fn fit_points(
+    points: &[Vec3],
+    look_transform: &LookTransform,
+    projection: &Projection,
+    grab_height: f32,
+    padding: f32,
+) -> Option<Fit> {
+    let forward = look_transform.look_direction()?;
+    let right = forward.cross(Vec3::Y).try_normalize()?;
+    let up = right.cross(forward);
+
+    if points.is_empty() {
+        return None;
+    }
+
+    let (min, max) = points.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), point| (min.min(*point), max.max(*point)),
+    );
+    let center = (min + max) / 2.0;
+
+    // Follow the view ray through the center to the grab plane
+    let target = if forward.y < -f32::EPSILON {
+        center + forward * (grab_height - center.y) / forward.y
+    } else {
+        Vec3::new(center.x, grab_height, center.z)
+    };
+
+    // Positions relative to the target, along the right and up axes of the view and towards the target
+    let view_points = points.iter().map(|point| {
+        let offset = *point - target;
+        (
+            offset.dot(right).abs(),
+            offset.dot(up).abs(),
+            offset.dot(forward),
+        )
+    });
+
+    let view_fraction = (1.0 - 2.0 * padding).clamp(0.01, 1.0);
+
+    match projection {
+        Projection::Perspective(perspective) => {
+            let tan_y = (perspective.fov / 2.0).tan() * view_fraction;
+            let tan_x = tan_y * perspective.aspect_ratio;
+
+            // The point has to be at least this deep in front of the eye to be in view
+            let radius = view_points
+                .map(|(x, y, depth)| (x / tan_x).max(y / tan_y) - depth)
+                .fold(0.0, f32::max);
+
+            Some(Fit {
+                target,
+                radius,
+                orthographic_scale: None,
+            })
+        }
+        Projection::Orthographic(orthographic) => {
+            let half_width = orthographic.area.width() / 2.0 * view_fraction;
+            let half_height = orthographic.area.height() / 2.0 * view_fraction;
+            if half_width <= 0.0 || half_height <= 0.0 {
+                return None;
+            }
+
+            let scale_factor = view_points
+                .map(|(x, y, _)| (x / half_width).max(y / half_height))
+                .fold(0.0, f32::max);
+
+            Some(Fit {
+                target,
+                radius: look_transform.radius(),
+                orthographic_scale: Some(orthographic.scale * scale_factor),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn aabb_corners(aabb: &Aabb) -> impl Iterator<Item = Vec3> + '_ {
+    (0..8).map(|corner| {
+        let sign = Vec3A::new(
+            if corner & 1 == 0 { -1.0 } else { 1.0 },
+            if corner & 2 == 0 { -1.0 } else { 1.0 },
+            if corner & 4 == 0 { -1.0 } else { 1.0 },
+        );
+        Vec3::from(aabb.center + aabb.half_extents * sign)
+    })
+}
+
+pub(super) fn fit_to_bounds(
+    mut commands: Commands,
+    mut fit_reader: MessageReader<FitToBounds>,
+    mut fly_to_writer: MessageWriter<FlyTo>,
+    mut finished_writer: MessageWriter<FlyToFinished>,
+    mut cameras: Query<(
+        &mut LookTransform,
+        &CameraController,
+        Option<&mut Projection>,
+        Has<Flight>,
+    )>,
+    entities: Query<(&GlobalTransform, Option<&Aabb>)>,
+    settings: ControllerSettings,
+) {
+    for fit_to_bounds in fit_reader.read() {
+        let Ok((mut look_transform, controller, projection, flying)) =
+            cameras.get_mut(fit_to_bounds.camera)
+        else {
+            continue;
+        };
+
+        let points = match &fit_to_bounds.bounds {
+            FitBounds::Aabb(aabb) => aabb_corners(aabb).collect(),
+            FitBounds::Points(points) => points.clone(),
+            FitBounds::Entities(fit_entities) => entities
+                .iter_many(fit_entities)
+                .flat_map(|(transform, aabb)| match aabb {
+                    Some(aabb) => aabb_corners(aabb)
+                        .map(|corner| transform.transform_point(corner))
+                        .collect(),
+                    None => vec![transform.translation()],
+                })
+                .collect::<Vec<_>>(),
+        };
+
+        let Some(fit) = projection.as_deref().and_then(|projection| {
+            fit_points(
+                &points,
+                &look_transform,
+                projection,
+                controller.grab_height,
+                fit_to_bounds.padding,
+            )
+        }) else {
+            continue;
+        };
+
+        let settings = settings.get(fit_to_bounds.camera);
+        let radius = fit
+            .radius
+            .min(settings.maximum_zoom)
+            .max(settings.minimum_zoom);
+        let orthographic_scale = fit.orthographic_scale.map(|scale| {
+            scale
+                .min(settings.maximum_orthographic_scale)
+                .max(settings.minimum_orthographic_scale)
+        });
+
+        if let Some(duration) = fit_to_bounds.duration {
+            let mut fly_to = FlyTo::new(fit_to_bounds.camera, fit.target)
+                .with_radius(radius)
+                .with_duration(duration);
+            fly_to.orthographic_scale = orthographic_scale;
+
+            fly_to_writer.write(fly_to);
+            continue;
+        }
+
+        let Some(look_direction) = look_transform.look_direction() else {
+            continue;
+        };
+
+        // Like any other input, fitting at once stops the flights and zooms that would move away again
+        if flying {
+            finished_writer.write(FlyToFinished {
+                camera: fit_to_bounds.camera,
+                interrupted: true,
+            });
+        }
+        commands
+            .entity(fit_to_bounds.camera)
+            .remove::<(Flight, ZoomAnimation)>();
+
+        look_transform.target = fit.target;
+        look_transform.eye = fit.target - look_direction * radius;
+
+        if let (Some(scale), Some(mut projection)) = (orthographic_scale, projection) {
+            if let Projection::Orthographic(orthographic) = &mut *projection {
+                orthographic.scale = scale;
+            }
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_camera::{OrthographicProjection, PerspectiveProjection};
+    use bevy_math::Rect;
+
+    fn points() -> Vec<Vec3> {
+        aabb_corners(&Aabb::from_min_max(
+            Vec3::new(10.0, 0.0, -5.0),
+            Vec3::new(30.0, 8.0, 5.0),
+        ))
+        .collect()
+    }
+
+    #[test]
+    fn test_fit_perspective() {
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let perspective = PerspectiveProjection {
+            aspect_ratio: 1.5,
+            ..Default::default()
+        };
+        let fit = fit_points(
+            &points(),
+            &look_transform,
+            &Projection::Perspective(perspective.clone()),
+            0.0,
+            0.0,
+        )
+        .unwrap();
+
+        assert_eq!(fit.target.y, 0.0);
+
+        let forward = look_transform.look_direction().unwrap();
+        let eye = fit.target - forward * fit.radius;
+        let view = bevy_transform::components::Transform::from_translation(eye)
+            .looking_to(forward, Vec3::Y)
+            .compute_affine()
+            .inverse();
+
+        let tan_y = (perspective.fov / 2.0).tan();
+        let tan_x = tan_y * perspective.aspect_ratio;
+
+        // Every point is in view and at least one touches the edge of the view
+        let mut largest = 0.0_f32;
+        for point in points() {
+            let view_point = view.transform_point3(point);
+            let depth = -view_point.z;
+            assert!(depth > 0.0);
+
+            let edge =
+                (view_point.x.abs() / (depth * tan_x)).max(view_point.y.abs() / (depth * tan_y));
+            assert!(edge <= 1.0 + 1e-4);
+            largest = largest.max(edge);
+        }
+        assert!((largest - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_fit_orthographic() {
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let orthographic = OrthographicProjection {
+            scale: 2.0,
+            area: Rect::new(-20.0, -10.0, 20.0, 10.0),
+            ..OrthographicProjection::default_3d()
+        };
+
+        let fit = fit_points(
+            &[Vec3::new(10.0, 0.0, 0.0), Vec3::new(30.0, 0.0, 0.0)],
+            &look_transform,
+            &Projection::Orthographic(orthographic),
+            0.0,
+            0.1,
+        )
+        .unwrap();
+
+        assert_eq!(fit.target, Vec3::new(20.0, 0.0, 0.0));
+        // The points are 20 wide, which is the 80% of the width that is left after the padding
+        assert!((fit.orthographic_scale.unwrap() - 2.0 * 20.0 / 32.0).abs() < 1e-4);
+        assert_eq!(fit.radius, look_transform.radius());
+    }
+
+    #[test]
+    fn test_instant_fit_stops_flight() {
+        use bevy_ecs::message::Messages;
+
+        use crate::test_utils::MapCameraTestApp;
+
+        let mut harness = MapCameraTestApp::new();
+        let camera = harness.camera;
+        harness
+            .app
+            .world_mut()
+            .write_message(FlyTo::new(camera, Vec3::new(100.0, 0.0, 0.0)));
+        harness.step_frames(5);
+
+        harness.app.world_mut().write_message(
+            FitToBounds::new(
+                camera,
+                FitBounds::Points(vec![Vec3::new(-5.0, 0.0, -5.0), Vec3::new(5.0, 0.0, 5.0)]),
+            )
+            .with_duration(None),
+        );
+        harness.step();
+
+        let finished = harness
+            .app
+            .world()
+            .resource::<Messages<FlyToFinished>>()
+            .iter_current_update_messages()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].interrupted);
+
+        let fitted = harness.look_transform();
+        assert!(fitted.target.abs_diff_eq(Vec3::ZERO, 1e-3));
+
+        harness.step_frames(10);
+        assert_eq!(harness.look_transform(), fitted);
+    }
+}
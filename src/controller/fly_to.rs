@@ -0,0 +1,444 @@
+use std::f32::consts::PI;
+
+use bevy_camera::Projection;
+use bevy_ecs::prelude::*;
+use bevy_math::{
+    FloatExt, Vec2, Vec3, Vec3Swizzles,
+    curve::{Curve, EaseFunction},
+};
+use bevy_time::Time;
+
+use super::{CameraController, ControlMessage, ControllerSettings, PanBounds, ZoomAnimation};
+use crate::{LookTransform, look_angles::LookAngles};
+
+/// Flies a [`CameraController`] camera to a new target over time, any input to the camera stops it.
+///
+/// The target is moved to the grab plane of the camera, and inside its [`PanBounds`]. The radius, yaw
+/// and pitch are kept within the limits of its
+/// [`CameraControllerSettings`](super::CameraControllerSettings), also on the way there. A
+/// [`FlyToFinished`] message is written when the flight ends. Cameras with an orthographic projection zoom with `orthographic_scale`, their radius
+/// only changes how far away the eye is.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct FlyTo {
+    pub camera: Entity,
+    pub target: Vec3,
+    /// Keeps the current radius if `None`
+    pub radius: Option<f32>,
+    /// In radians, keeps the current yaw if `None`
+    pub yaw: Option<f32>,
+    /// In radians, keeps the current pitch if `None`
+    pub pitch: Option<f32>,
+    /// The [`OrthographicProjection::scale`](bevy_camera::OrthographicProjection::scale) to end at,
+    /// keeps the current scale if `None`
+    pub orthographic_scale: Option<f32>,
+    /// In seconds
+    pub duration: f32,
+    pub easing: EaseFunction,
+    /// Zoom out on the way when the flight is longer than the radius, to keep the surroundings in view
+    pub arc: bool,
+}
+
+impl FlyTo {
+    pub fn new(camera: Entity, target: Vec3) -> Self {
+        Self {
+            camera,
+            target,
+            radius: None,
+            yaw: None,
+            pitch: None,
+            orthographic_scale: None,
+            duration: 1.0,
+            easing: EaseFunction::CubicInOut,
+            arc: true,
+        }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    pub fn with_yaw(mut self, yaw: f32) -> Self {
+        self.yaw = Some(yaw);
+        self
+    }
+
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = Some(pitch);
+        self
+    }
+
+    pub fn with_orthographic_scale(mut self, orthographic_scale: f32) -> Self {
+        self.orthographic_scale = Some(orthographic_scale);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: EaseFunction) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn without_arc(mut self) -> Self {
+        self.arc = false;
+        self
+    }
+}
+
+/// Written when the flight of a [`FlyTo`] ends
+#[derive(Message, Debug, Clone, Copy)]
+pub struct FlyToFinished {
+    pub camera: Entity,
+    /// If the flight was stopped by input, or by another [`FlyTo`], before it reached its target
+    pub interrupted: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Pose {
+    target: Vec3,
+    radius: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+/// An ongoing [`FlyTo`]
+#[derive(Component)]
+pub(super) struct Flight {
+    start: Pose,
+    end: Pose,
+    duration: f32,
+    elapsed: f32,
+    easing: EaseFunction,
+    /// The start and end scale of an orthographic projection
+    orthographic_scale: Option<(f32, f32)>,
+    /// How much further away, as the natural logarithm of the radius, the camera is halfway there
+    arc_height: f32,
+    /// Turn the shortest way around, which could leave a yaw range
+    shortest_turn: bool,
+}
+
+impl Flight {
+    fn look_transform(&self, t: f32) -> LookTransform {
+        let (start, end) = (self.start, self.end);
+
+        let yaw_delta = if self.shortest_turn {
+            (end.yaw - start.yaw + PI).rem_euclid(2.0 * PI) - PI
+        } else {
+            end.yaw - start.yaw
+        };
+
+        let mut look_angles = LookAngles::default();
+        look_angles.set_yaw(start.yaw + yaw_delta * t);
+        look_angles.set_pitch(start.pitch.lerp(end.pitch, t));
+
+        // Interpolate the radius logarithmically so that the zoom speed feels the same at any distance
+        let radius =
+            (start.radius.ln().lerp(end.radius.ln(), t) + self.arc_height * (PI * t).sin()).exp();
+
+        let target = start.target.lerp(end.target, t);
+
+        LookTransform::new(target + radius * look_angles.unit_vector(), target, Vec3::Y)
+    }
+
+    fn orthographic_scale(&self, t: f32) -> Option<f32> {
+        self.orthographic_scale
+            .map(|(start, end)| start.ln().lerp(end.ln(), t).exp())
+    }
+}
+
+pub(super) fn start_flights(
+    mut commands: Commands,
+    mut fly_to_reader: MessageReader<FlyTo>,
+    mut finished_writer: MessageWriter<FlyToFinished>,
+    cameras: Query<(
+        &LookTransform,
+        &CameraController,
+        Option<&Projection>,
+        Has<Flight>,
+    )>,
+    bounds: Query<&PanBounds>,
+    settings: ControllerSettings,
+) {
+    for fly_to in fly_to_reader.read() {
+        let Ok((look_transform, controller, projection, flying)) = cameras.get(fly_to.camera)
+        else {
+            continue;
+        };
+        let Some(look_direction) = look_transform.look_direction() else {
+            continue;
+        };
+
+        if flying {
+            finished_writer.write(FlyToFinished {
+                camera: fly_to.camera,
+                interrupted: true,
+            });
+        }
+
+        let settings = settings.get(fly_to.camera);
+        // Within a yaw range both ends are in the turn around the range, the flight turns inside it
+        let mut look_angles = LookAngles::from_vector(-look_direction);
+        settings.limit_look_angles(&mut look_angles);
+
+        let start = Pose {
+            target: look_transform.target,
+            radius: look_transform.radius(),
+            yaw: look_angles.get_yaw(),
+            pitch: look_angles.get_pitch(),
+        };
+
+        let mut end_angles = look_angles;
+        end_angles.set_yaw(fly_to.yaw.unwrap_or(start.yaw));
+        end_angles.set_pitch(fly_to.pitch.unwrap_or(start.pitch));
+        settings.limit_look_angles(&mut end_angles);
+
+        // Inside the bounds, the soft margin would spring back after landing
+        let target = bounds
+            .get(fly_to.camera)
+            .map_or(fly_to.target.xz(), |bounds| {
+                bounds.shape.closest_point(fly_to.target.xz())
+            });
+
+        let end = Pose {
+            target: Vec3::new(target.x, controller.grab_height, target.y),
+            radius: fly_to
+                .radius
+                .unwrap_or(start.radius)
+                .min(settings.maximum_zoom)
+                .max(settings.minimum_zoom),
+            yaw: end_angles.get_yaw(),
+            pitch: end_angles.get_pitch(),
+        };
+
+        let orthographic_scale = match projection {
+            Some(Projection::Orthographic(orthographic)) => Some((
+                orthographic.scale,
+                fly_to
+                    .orthographic_scale
+                    .unwrap_or(orthographic.scale)
+                    .min(settings.maximum_orthographic_scale)
+                    .max(settings.minimum_orthographic_scale),
+            )),
+            _ => None,
+        };
+
+        let distance = start.target.distance(end.target);
+        let arc_height = if fly_to.arc {
+            (distance.ln() - start.radius.max(end.radius).ln()).max(0.0)
+        } else {
+            0.0
+        };
+
+        commands
+            .entity(fly_to.camera)
+            .remove::<ZoomAnimation>()
+            .insert(Flight {
+                start,
+                end,
+                duration: fly_to.duration,
+                elapsed: 0.0,
+                easing: fly_to.easing,
+                orthographic_scale,
+                arc_height,
+                shortest_turn: settings.yaw_range.is_none(),
+            });
+    }
+}
+
+pub(super) fn fly(
+    mut commands: Commands,
+    mut events: MessageReader<ControlMessage>,
+    mut finished_writer: MessageWriter<FlyToFinished>,
+    mut cameras: Query<(
+        Entity,
+        &mut LookTransform,
+        &mut Flight,
+        Option<&mut Projection>,
+    )>,
+    time: Res<Time>,
+) {
+    // Buttons that are held still send messages without movement, those don't stop the flight
+    let moved_cameras = events
+        .read()
+        .filter(|event| match event {
+            ControlMessage::Orbit(_, delta) => *delta != Vec2::ZERO,
+            ControlMessage::TranslateTarget(_, delta) => *delta != Vec3::ZERO,
+            ControlMessage::Zoom { zoom_scalar, .. } => *zoom_scalar != 1.0,
+            ControlMessage::OrbitAround { delta, .. } => *delta != Vec2::ZERO,
+        })
+        .map(ControlMessage::camera)
+        .collect::<Vec<_>>();
+
+    for (camera, mut look_transform, mut flight, projection) in cameras.iter_mut() {
+        let interrupted = moved_cameras.contains(&camera);
+
+        if !interrupted {
+            flight.elapsed += time.delta_secs();
+            let t = flight
+                .easing
+                .sample_clamped(flight.elapsed / flight.duration.max(f32::EPSILON));
+            *look_transform = flight.look_transform(t);
+
+            if let (Some(scale), Some(mut projection)) = (flight.orthographic_scale(t), projection)
+            {
+                if let Projection::Orthographic(orthographic) = &mut *projection {
+                    orthographic.scale = scale;
+                }
+            }
+        }
+
+        if interrupted || flight.elapsed >= flight.duration {
+            commands.entity(camera).remove::<Flight>();
+            finished_writer.write(FlyToFinished {
+                camera,
+                interrupted,
+            });
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_app::prelude::*;
+    use bevy_ecs::message::Messages;
+    use core::time::Duration;
+
+    use crate::controller::{control_system, tests::control_app};
+
+    fn fly_to_app() -> (App, Entity) {
+        let mut app = control_app(Duration::from_secs_f64(1.0 / 60.0));
+        app.add_message::<FlyTo>();
+        app.add_message::<FlyToFinished>();
+        app.add_systems(
+            PreUpdate,
+            (
+                start_flights.before(control_system),
+                fly.after(control_system),
+            ),
+        );
+
+        let camera = app
+            .world_mut()
+            .spawn((
+                LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y),
+                CameraController::default(),
+            ))
+            .id();
+
+        (app, camera)
+    }
+
+    fn finished(app: &App) -> Vec<FlyToFinished> {
+        app.world()
+            .resource::<Messages<FlyToFinished>>()
+            .iter_current_update_messages()
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn test_fly_to_reaches_end() {
+        let (mut app, camera) = fly_to_app();
+
+        let target = Vec3::new(100.0, 0.0, -50.0);
+        app.world_mut().write_message(
+            FlyTo::new(camera, target)
+                .with_radius(20.0)
+                .with_yaw(1.0)
+                .with_pitch(0.8)
+                .with_duration(0.5),
+        );
+
+        let mut max_radius = 0.0_f32;
+        let mut finished_messages = Vec::new();
+        for _ in 0..40 {
+            app.update();
+            max_radius = max_radius.max(app.world().get::<LookTransform>(camera).unwrap().radius());
+            finished_messages.extend(finished(&app));
+        }
+
+        let look_transform = app.world().get::<LookTransform>(camera).unwrap();
+        let look_angles = LookAngles::from_vector(-look_transform.look_direction().unwrap());
+
+        assert!(look_transform.target.abs_diff_eq(target, 1e-3));
+        assert!((look_transform.radius() - 20.0).abs() < 1e-3);
+        assert!((look_angles.get_yaw() - 1.0).abs() < 1e-3);
+        assert!((look_angles.get_pitch() - 0.8).abs() < 1e-3);
+        // Zoomed out on the way
+        assert!(max_radius > 50.0);
+
+        assert_eq!(finished_messages.len(), 1);
+        assert!(!finished_messages[0].interrupted);
+    }
+
+    #[test]
+    fn test_input_interrupts_fly_to() {
+        let (mut app, camera) = fly_to_app();
+
+        app.world_mut()
+            .write_message(FlyTo::new(camera, Vec3::new(10.0, 0.0, 0.0)));
+        app.update();
+        app.update();
+
+        app.world_mut()
+            .write_message(ControlMessage::Orbit(camera, Vec2::new(0.1, 0.0)));
+        app.update();
+
+        assert!(app.world().get::<Flight>(camera).is_none());
+        let finished_messages = finished(&app);
+        assert_eq!(finished_messages.len(), 1);
+        assert!(finished_messages[0].interrupted);
+    }
+
+    #[test]
+    fn test_fly_to_stays_within_limits() {
+        use bevy_math::Rect;
+
+        use crate::controller::{BoundsShape, CameraControllerSettings};
+
+        let (mut app, camera) = fly_to_app();
+        let mut look_angles = LookAngles::default();
+        look_angles.set_yaw(2.4);
+        look_angles.set_pitch(0.8);
+        app.world_mut().entity_mut(camera).insert((
+            LookTransform::new(20.0 * look_angles.unit_vector(), Vec3::ZERO, Vec3::Y),
+            CameraControllerSettings::default().with_yaw_range(-2.5, 2.5),
+            PanBounds::new(BoundsShape::Rect(Rect::new(-10.0, -10.0, 10.0, 10.0)))
+                .with_soft_margin(5.0),
+        ));
+
+        // The shortest turn to the end yaw is through the half turn outside the yaw range
+        app.world_mut().write_message(
+            FlyTo::new(camera, Vec3::new(50.0, 0.0, 0.0))
+                .with_yaw(-2.4)
+                .with_duration(0.5),
+        );
+
+        for _ in 0..40 {
+            app.update();
+            let look_transform = app.world().get::<LookTransform>(camera).unwrap();
+            let yaw = LookAngles::from_vector(-look_transform.look_direction().unwrap()).get_yaw();
+            assert!(yaw.abs() <= 2.5 + 1e-3, "{yaw}");
+        }
+
+        let look_transform = app.world().get::<LookTransform>(camera).unwrap();
+        assert!(
+            look_transform
+                .target
+                .abs_diff_eq(Vec3::new(10.0, 0.0, 0.0), 1e-3)
+        );
+    }
+}
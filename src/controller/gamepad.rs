@@ -0,0 +1,221 @@
+use bevy_app::prelude::*;
+use bevy_camera::Projection;
+use bevy_ecs::prelude::*;
+use bevy_ecs::reflect::ReflectResource;
+use bevy_input::gamepad::{Gamepad, GamepadButton};
+use bevy_math::Vec2;
+use bevy_reflect::Reflect;
+use bevy_time::Time;
+
+use super::{ControlMessage, ControllerSettings, PointerCameras, view_aligned_translation};
+use crate::{CameraChange, LookTransform};
+
+/// Settings for how gamepads control the camera, the speeds are set per camera in
+/// [`CameraControllerSettings`](super::CameraControllerSettings).
+///
+/// The left stick pans, the right stick orbits, the right trigger zooms in and the left trigger zooms out.
+#[derive(Resource, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+pub struct GamepadInputSettings {
+    /// Enabled by default
+    pub enabled: bool,
+    /// The gamepad entity that controls the camera, `None` lets every connected gamepad control it
+    pub gamepad: Option<Entity>,
+    /// Stick movements shorter than this, in the range `0.0..1.0`, are ignored
+    pub stick_deadzone: f32,
+    /// Trigger values smaller than this, in the range `0.0..1.0`, are ignored
+    pub trigger_deadzone: f32,
+}
+
+impl Default for GamepadInputSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gamepad: None,
+            stick_deadzone: 0.15,
+            trigger_deadzone: 0.1,
+        }
+    }
+}
+
+impl GamepadInputSettings {
+    pub fn with_gamepad(mut self, gamepad: Entity) -> Self {
+        self.gamepad = Some(gamepad);
+        self
+    }
+}
+
+pub(super) struct GamepadController;
+
+impl Plugin for GamepadController {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GamepadInputSettings>();
+        app.init_resource::<GamepadInputSettings>();
+
+        app.add_systems(
+            PreUpdate,
+            gamepad_control
+                .run_if(|settings: Res<GamepadInputSettings>| settings.enabled)
+                .in_set(CameraChange::Before),
+        );
+    }
+}
+
+/// Pans, rotates and zooms the camera with the sticks and triggers of the gamepads
+fn gamepad_control(
+    gamepads: Query<(Entity, &Gamepad)>,
+    cameras: Query<(&LookTransform, Option<&Projection>)>,
+    gamepad_settings: Res<GamepadInputSettings>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    time: Res<Time>,
+    mut camera_writer: MessageWriter<ControlMessage>,
+) {
+    let mut pan = Vec2::ZERO;
+    let mut orbit = Vec2::ZERO;
+    let mut zoom = 0.0;
+
+    for (_, gamepad) in gamepads.iter().filter(|(entity, _)| {
+        gamepad_settings
+            .gamepad
+            .is_none_or(|gamepad| gamepad == *entity)
+    }) {
+        let trigger = |button| {
+            apply_deadzone(
+                gamepad.get(button).unwrap_or(0.0),
+                gamepad_settings.trigger_deadzone,
+            )
+        };
+
+        pan += apply_stick_deadzone(gamepad.left_stick(), gamepad_settings.stick_deadzone);
+        orbit += apply_stick_deadzone(gamepad.right_stick(), gamepad_settings.stick_deadzone);
+        zoom += trigger(GamepadButton::LeftTrigger2) - trigger(GamepadButton::RightTrigger2);
+    }
+
+    if pan == Vec2::ZERO && orbit == Vec2::ZERO && zoom == 0.0 {
+        return;
+    }
+
+    let Some(camera) = pointer_cameras.under_cursor_or_top() else {
+        return;
+    };
+
+    let Ok((look_transform, projection)) = cameras.get(camera) else {
+        return;
+    };
+
+    let settings = settings.get(camera);
+    let delta_seconds = time.delta_secs();
+
+    if pan != Vec2::ZERO {
+        camera_writer.write(ControlMessage::TranslateTarget(
+            camera,
+            view_aligned_translation(
+                look_transform,
+                projection,
+                pan.clamp_length_max(1.0) * settings.gamepad_pan_speed * delta_seconds,
+            ),
+        ));
+    }
+
+    if orbit != Vec2::ZERO {
+        camera_writer.write(ControlMessage::Orbit(
+            camera,
+            orbit.clamp_length_max(1.0) * settings.gamepad_rotation_speed * delta_seconds,
+        ));
+    }
+
+    if zoom != 0.0 {
+        // The target is in the centre of the screen
+        camera_writer.write(ControlMessage::Zoom {
+            camera,
+            zoom_scalar: settings
+                .gamepad_zoom_speed
+                .powf(zoom.clamp(-1.0, 1.0) * delta_seconds),
+            zoom_target: look_transform.target,
+        });
+    }
+}
+
+/// Ignores values inside the deadzone and rescales the rest to start from zero at its edge
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    if value.abs() <= deadzone {
+        return 0.0;
+    }
+
+    value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+}
+
+fn apply_stick_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+
+    stick * (apply_deadzone(length, deadzone) / length)
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_camera::Camera;
+    use bevy_input::gamepad::GamepadAxis;
+    use bevy_math::Vec3;
+    use bevy_window::{PrimaryWindow, Window};
+    use core::time::Duration;
+
+    use crate::{
+        CameraController,
+        controller::{control_system, tests::control_app},
+    };
+
+    #[test]
+    fn test_stick_deadzone() {
+        assert_eq!(apply_stick_deadzone(Vec2::new(0.1, 0.0), 0.15), Vec2::ZERO);
+        assert!(apply_stick_deadzone(Vec2::new(0.0, -1.0), 0.15).abs_diff_eq(Vec2::NEG_Y, 1e-6));
+        assert!((apply_deadzone(0.575, 0.15) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sticks_and_triggers_move_camera() {
+        let mut app = control_app(Duration::from_millis(500));
+        app.init_resource::<GamepadInputSettings>();
+        app.add_systems(PreUpdate, gamepad_control.before(control_system));
+
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                CameraController::default(),
+                look_transform,
+                Projection::default(),
+            ))
+            .id();
+
+        let mut gamepad = Gamepad::default();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, 1.0);
+        gamepad.analog_mut().set(GamepadButton::RightTrigger2, 1.0);
+        app.world_mut().spawn(gamepad);
+
+        // The first update starts the clock
+        app.update();
+        app.update();
+
+        let new_look_transform = app.world().get::<LookTransform>(camera).unwrap();
+
+        // Right is towards positive X when looking from positive Z
+        assert!(new_look_transform.target.x > 0.0);
+        assert!(new_look_transform.target.z.abs() < 1e-5);
+        assert!(new_look_transform.radius() < look_transform.radius());
+    }
+}
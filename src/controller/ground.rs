@@ -0,0 +1,328 @@
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+use bevy_math::{Dir3, Ray3d, UVec2, Vec2, Vec3, Vec3Swizzles, primitives::InfinitePlane3d};
+
+use super::CameraController;
+use crate::LookTransform;
+
+/// How many times the step between two samples is halved to find where a ray hits a [`Heightmap`]
+const BISECTION_STEPS: usize = 16;
+
+/// Finds the ground under the pointer for a [`CameraController`] camera
+pub trait GroundQuery: Send + Sync + 'static {
+    /// The first point where `ray` hits the ground
+    fn intersect(&self, ray: Ray3d) -> Option<Vec3>;
+
+    /// The height of the ground at `position` on the XZ plane, the `x` of `position` is the world X axis
+    /// and the `y` is the world Z axis
+    fn height_at(&self, position: Vec2) -> Option<f32>;
+}
+
+/// The ground of a [`CameraController`] camera, used to grab pan, zoom towards the pointer and keep the
+/// target on the ground.
+///
+/// The [`CameraController::grab_height`] follows the height of the ground under the target. Rays that
+/// miss the ground hit the grab plane instead. Cameras without a `Ground` only use the grab plane.
+#[derive(Component, Clone)]
+pub struct Ground(pub Arc<dyn GroundQuery>);
+
+impl Ground {
+    pub fn new(ground: impl GroundQuery) -> Self {
+        Self(Arc::new(ground))
+    }
+}
+
+/// A flat ground at a fixed height
+#[derive(Debug, Clone, Copy)]
+pub struct FlatGround {
+    pub height: f32,
+}
+
+impl GroundQuery for FlatGround {
+    fn intersect(&self, ray: Ray3d) -> Option<Vec3> {
+        plane_point(ray, self.height)
+    }
+
+    fn height_at(&self, _position: Vec2) -> Option<f32> {
+        Some(self.height)
+    }
+}
+
+/// A grid of heights on the XZ plane, bilinearly interpolated between the samples
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    /// The heights row by row, a row goes along the X axis and the rows go along the Z axis
+    pub heights: Vec<f32>,
+    /// The number of samples along X and Z
+    pub size: UVec2,
+    /// Where the first sample is on the XZ plane
+    pub origin: Vec2,
+    /// The distance between two samples, in world units
+    pub spacing: f32,
+}
+
+impl Heightmap {
+    /// # Panics
+    ///
+    /// If `size` has no samples along X or Z, `heights` doesn't have `size.x * size.y` samples, or
+    /// `spacing` isn't positive
+    pub fn new(heights: Vec<f32>, size: UVec2, origin: Vec2, spacing: f32) -> Self {
+        assert!(
+            size.x > 0 && size.y > 0,
+            "The heightmap needs at least one sample along X and Z, got {size}"
+        );
+        assert!(
+            spacing > 0.0,
+            "The heightmap spacing needs to be positive, got {spacing}"
+        );
+        assert_eq!(
+            heights.len(),
+            (size.x * size.y) as usize,
+            "The heightmap needs one height per sample"
+        );
+
+        Self {
+            heights,
+            size,
+            origin,
+            spacing,
+        }
+    }
+
+    fn sample(&self, x: u32, z: u32) -> f32 {
+        self.heights[(z * self.size.x + x) as usize]
+    }
+
+    /// The corners of the heightmap on the XZ plane
+    fn extent(&self) -> (Vec2, Vec2) {
+        let size = (self.size.as_vec2() - 1.0).max(Vec2::ZERO) * self.spacing;
+        (self.origin, self.origin + size)
+    }
+
+    /// The height at `position`, or at the closest point on the heightmap if `position` is outside of
+    /// it. The heightmap can't be empty.
+    fn clamped_height(&self, position: Vec2) -> f32 {
+        let last = (self.size - 1).as_vec2();
+        let grid = ((position - self.origin) / self.spacing).clamp(Vec2::ZERO, last);
+
+        let x0 = (grid.x.floor() as u32).min(self.size.x.saturating_sub(2));
+        let z0 = (grid.y.floor() as u32).min(self.size.y.saturating_sub(2));
+        let x1 = (x0 + 1).min(self.size.x - 1);
+        let z1 = (z0 + 1).min(self.size.y - 1);
+        let fraction = grid - Vec2::new(x0 as f32, z0 as f32);
+
+        let near = self.sample(x0, z0) + (self.sample(x1, z0) - self.sample(x0, z0)) * fraction.x;
+        let far = self.sample(x0, z1) + (self.sample(x1, z1) - self.sample(x0, z1)) * fraction.x;
+        near + (far - near) * fraction.y
+    }
+}
+
+impl GroundQuery for Heightmap {
+    fn intersect(&self, ray: Ray3d) -> Option<Vec3> {
+        if self.size.x == 0 || self.size.y == 0 {
+            return None;
+        }
+        let (min, max) = self.extent();
+
+        // The part of the ray that is above the heightmap, slab test on the XZ plane
+        let mut start = 0.0_f32;
+        let mut end = f32::MAX;
+        for (origin, direction, min, max) in [
+            (ray.origin.x, ray.direction.x, min.x, max.x),
+            (ray.origin.z, ray.direction.z, min.y, max.y),
+        ] {
+            if direction.abs() < f32::EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let (near, far) = ((min - origin) / direction, (max - origin) / direction);
+            start = start.max(near.min(far));
+            end = end.min(near.max(far));
+        }
+        if start > end {
+            return None;
+        }
+
+        // Points between `start` and `end` are on the heightmap, clamping keeps the ones that are a
+        // rounding error outside of it on the edge
+        let height_above = |distance: f32| {
+            let point = ray.get_point(distance);
+            point.y - self.clamped_height(point.xz())
+        };
+
+        // Rays looking straight down don't travel across the heightmap
+        if ray.direction.xz().length() < f32::EPSILON {
+            let height = height_above(start);
+            if height <= 0.0 {
+                return Some(ray.get_point(start));
+            }
+            return (ray.direction.y < 0.0)
+                .then(|| ray.get_point(start + height / -ray.direction.y));
+        }
+
+        // March along the ray until it is below the ground, then bisect between the last two steps
+        let step = self.spacing / 2.0 / ray.direction.xz().length();
+        let mut previous = start;
+        if height_above(previous) <= 0.0 {
+            return Some(ray.get_point(previous));
+        }
+
+        loop {
+            let distance = (previous + step).min(end);
+            if height_above(distance) <= 0.0 {
+                let (mut above, mut below) = (previous, distance);
+                for _ in 0..BISECTION_STEPS {
+                    let middle = (above + below) / 2.0;
+                    if height_above(middle) > 0.0 {
+                        above = middle;
+                    } else {
+                        below = middle;
+                    }
+                }
+                return Some(ray.get_point(below));
+            }
+
+            if distance >= end {
+                return None;
+            }
+            previous = distance;
+        }
+    }
+
+    fn height_at(&self, position: Vec2) -> Option<f32> {
+        if self.size.x == 0 || self.size.y == 0 {
+            return None;
+        }
+
+        let grid = (position - self.origin) / self.spacing;
+        let last = (self.size - 1).as_vec2();
+        if grid.x < 0.0 || grid.y < 0.0 || grid.x > last.x || grid.y > last.y {
+            return None;
+        }
+
+        Some(self.clamped_height(position))
+    }
+}
+
+/// Where `ray` hits the horizontal plane at `height`
+pub(super) fn plane_point(ray: Ray3d, height: f32) -> Option<Vec3> {
+    ray.intersect_plane(Vec3::Y * height, InfinitePlane3d { normal: Dir3::Y })
+        .map(|distance| ray.get_point(distance))
+}
+
+/// Where `ray` hits the [`Ground`] of a camera, or its grab plane if it has no ground or the ray misses
+pub(super) fn ground_point(
+    ray: Ray3d,
+    controller: &CameraController,
+    ground: Option<&Ground>,
+) -> Option<Vec3> {
+    ground
+        .and_then(|ground| ground.0.intersect(ray))
+        .or_else(|| plane_point(ray, controller.grab_height))
+}
+
+/// Moves the grab height of cameras with a [`Ground`] to the height of the ground under their target
+pub(super) fn follow_ground(mut cameras: Query<(&LookTransform, &mut CameraController, &Ground)>) {
+    for (look_transform, mut controller, ground) in cameras.iter_mut() {
+        if !controller.enabled {
+            continue;
+        }
+
+        if let Some(height) = ground.0.height_at(look_transform.target.xz()) {
+            if controller.grab_height != height {
+                controller.grab_height = height;
+            }
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A slope rising one unit per unit along X, from `x = 0` to `x = 10`
+    fn slope() -> Heightmap {
+        Heightmap::new(
+            (0..4).flat_map(|_| (0..11).map(|x| x as f32)).collect(),
+            UVec2::new(11, 4),
+            Vec2::ZERO,
+            1.0,
+        )
+    }
+
+    #[test]
+    fn test_heightmap_height_is_interpolated() {
+        let heightmap = slope();
+
+        assert_eq!(heightmap.height_at(Vec2::new(2.5, 1.5)), Some(2.5));
+        assert_eq!(heightmap.height_at(Vec2::new(10.0, 3.0)), Some(10.0));
+        assert_eq!(heightmap.height_at(Vec2::new(10.5, 1.0)), None);
+        assert_eq!(heightmap.height_at(Vec2::new(1.0, -0.5)), None);
+    }
+
+    #[test]
+    fn test_ray_hits_heightmap() {
+        let heightmap = slope();
+
+        // Looking down along the slope, hits where the height is the same as the distance along X
+        let ray = Ray3d::new(
+            Vec3::new(0.0, 8.0, 1.5),
+            Dir3::new(Vec3::new(1.0, -1.0, 0.0)).unwrap(),
+        );
+        let hit = heightmap.intersect(ray).unwrap();
+        assert!(hit.abs_diff_eq(Vec3::new(4.0, 4.0, 1.5), 1e-3));
+
+        // Straight down
+        let ray = Ray3d::new(Vec3::new(6.5, 20.0, 2.0), Dir3::NEG_Y);
+        let hit = heightmap.intersect(ray).unwrap();
+        assert!(hit.abs_diff_eq(Vec3::new(6.5, 6.5, 2.0), 1e-3));
+
+        // Passes above the heightmap
+        let ray = Ray3d::new(Vec3::new(0.0, 20.0, 1.5), Dir3::X);
+        assert!(heightmap.intersect(ray).is_none());
+    }
+
+    #[test]
+    fn test_rays_entering_from_the_side_hit_heightmap() {
+        let heightmap = Heightmap::new(
+            (0..35).map(|i| (i % 7) as f32 * 0.3).collect(),
+            UVec2::new(7, 5),
+            Vec2::new(-1.3, 2.7),
+            0.37,
+        );
+
+        let on_ground = Vec2::new(-1.3 + 0.37 * 3.1, 2.7 + 0.37 * 1.9);
+        let target = Vec3::new(
+            on_ground.x,
+            heightmap.height_at(on_ground).unwrap(),
+            on_ground.y,
+        );
+
+        // Rays from outside the heightmap towards a point on it, the first sample lands on its edge
+        for i in 0..200 {
+            let angle = i as f32 * 0.1;
+            let origin =
+                target + Vec3::new(angle.cos() * 7.0, 5.0 + i as f32 * 0.013, angle.sin() * 7.0);
+            let ray = Ray3d::new(origin, Dir3::new(target - origin).unwrap());
+
+            assert!(heightmap.intersect(ray).is_some(), "{ray:?}");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "spacing needs to be positive")]
+    fn test_heightmap_needs_positive_spacing() {
+        Heightmap::new(vec![0.0; 4], UVec2::new(2, 2), Vec2::ZERO, 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::VecDeque;
+
+use bevy_ecs::{
+    entity::Entity,
+    message::{Message, MessageWriter},
+    system::SystemParam,
+};
+use bevy_math::{Vec2, Vec3};
+
+use super::{ControlMessage, InertiaSettings};
+
+/// Stop gliding once the speed has decayed to this fraction of the release speed
+const STOP_FRACTION: f32 = 0.01;
+
+/// Zooms need this many frames of input in the sample window to glide, so that single scroll
+/// wheel steps don't
+const MIN_ZOOM_SAMPLES: usize = 3;
+
+/// Sent every frame that a pointer drags a camera, also when it holds the camera still. Only the end
+/// of a drag leaves the camera gliding.
+#[derive(Message, Clone, Copy)]
+pub(super) struct Dragging(pub Entity);
+
+/// Writes the [`ControlMessage`]s of a pointer, and which cameras it drags
+#[derive(SystemParam)]
+pub(super) struct ControlWriter<'w> {
+    control: MessageWriter<'w, ControlMessage>,
+    dragging: MessageWriter<'w, Dragging>,
+}
+
+impl ControlWriter<'_> {
+    pub fn write(&mut self, event: ControlMessage) {
+        self.control.write(event);
+    }
+
+    /// Marks `camera` as dragged this frame, it glides once this stops
+    pub fn drag(&mut self, camera: Entity) {
+        self.dragging.write(Dragging(camera));
+    }
+}
+
+/// The movement of a camera during a frame, or per second when gliding
+#[derive(Default, Clone, Copy, PartialEq)]
+struct Motion {
+    translation: Vec3,
+    orbit: Vec2,
+    /// What the orbit turns around, the target if `None`
+    pivot: Option<Vec3>,
+    /// The natural logarithm of the zoom scalar, so that zooms can be summed and scaled
+    zoom: f32,
+    zoom_target: Option<Vec3>,
+}
+
+struct Glide {
+    velocity: Motion,
+    /// The fraction of the release speed that is left
+    remaining: f32,
+}
+
+/// Keeps the recent movement of a camera to continue it with a glide when the input stops
+#[derive(Default)]
+pub(super) struct Inertia {
+    samples: VecDeque<(f32, Motion)>,
+    glide: Option<Glide>,
+}
+
+impl Inertia {
+    /// Records the movement of the messages that a camera got this frame, which stops any glide
+    pub fn record(&mut self, now: f32, events: &[ControlMessage], settings: &InertiaSettings) {
+        self.glide = None;
+
+        let mut motion = Motion::default();
+        for event in events {
+            match event {
+                ControlMessage::Orbit(_, delta) => motion.orbit += *delta,
+                ControlMessage::OrbitAround { delta, pivot, .. } => {
+                    motion.orbit += *delta;
+                    motion.pivot = Some(*pivot);
+                }
+                ControlMessage::TranslateTarget(_, delta) => motion.translation += *delta,
+                ControlMessage::Zoom {
+                    zoom_scalar,
+                    zoom_target,
+                    ..
+                } => {
+                    motion.zoom += zoom_scalar.ln();
+                    motion.zoom_target = Some(*zoom_target);
+                }
+            }
+        }
+
+        self.samples.push_back((now, motion));
+        self.remove_old_samples(now, settings);
+    }
+
+    /// Returns the messages that continue the movement of `camera` this frame, starting a glide if
+    /// the input stopped since last frame.
+    pub fn glide(
+        &mut self,
+        camera: Entity,
+        delta_seconds: f32,
+        settings: &InertiaSettings,
+    ) -> Vec<ControlMessage> {
+        if !self.samples.is_empty() {
+            self.start_glide(settings);
+        }
+
+        let Some(glide) = &mut self.glide else {
+            return Vec::new();
+        };
+
+        let velocity = glide.velocity;
+        let mut events = Vec::new();
+
+        if velocity.translation != Vec3::ZERO {
+            events.push(ControlMessage::TranslateTarget(
+                camera,
+                velocity.translation * delta_seconds,
+            ));
+        }
+        if velocity.orbit != Vec2::ZERO {
+            let delta = velocity.orbit * delta_seconds;
+            events.push(match velocity.pivot {
+                Some(pivot) => ControlMessage::OrbitAround {
+                    camera,
+                    delta,
+                    pivot,
+                },
+                None => ControlMessage::Orbit(camera, delta),
+            });
+        }
+        if let Some(zoom_target) = velocity.zoom_target.filter(|_| velocity.zoom != 0.0) {
+            events.push(ControlMessage::Zoom {
+                camera,
+                zoom_scalar: (velocity.zoom * delta_seconds).exp(),
+                zoom_target,
+            });
+        }
+
+        let decay = (-settings.friction * delta_seconds).exp();
+        glide.velocity.translation *= decay;
+        glide.velocity.orbit *= decay;
+        glide.velocity.zoom *= decay;
+        glide.remaining *= decay;
+
+        if glide.remaining < STOP_FRACTION {
+            self.glide = None;
+        }
+
+        events
+    }
+
+    /// If the camera is still moving, or was moved by input recently
+    pub fn is_active(&self) -> bool {
+        self.glide.is_some() || !self.samples.is_empty()
+    }
+
+    /// Starts gliding with the average speed of the samples, which end the frame before the release
+    fn start_glide(&mut self, settings: &InertiaSettings) {
+        let mut velocity = Motion::default();
+        let mut zoom_samples = 0;
+        for (_, motion) in self.samples.drain(..) {
+            velocity.translation += motion.translation;
+            velocity.orbit += motion.orbit;
+            velocity.pivot = motion.pivot.or(velocity.pivot);
+            if motion.zoom != 0.0 {
+                velocity.zoom += motion.zoom;
+                velocity.zoom_target = motion.zoom_target;
+                zoom_samples += 1;
+            }
+        }
+
+        if zoom_samples < MIN_ZOOM_SAMPLES {
+            velocity.zoom = 0.0;
+        }
+
+        let sample_window = settings.sample_window.max(f32::EPSILON);
+        velocity.translation /= sample_window;
+        velocity.orbit /= sample_window;
+        velocity.zoom /= sample_window;
+
+        self.glide = (velocity.translation != Vec3::ZERO
+            || velocity.orbit != Vec2::ZERO
+            || velocity.zoom != 0.0)
+            .then_some(Glide {
+                velocity,
+                remaining: 1.0,
+            });
+    }
+
+    fn remove_old_samples(&mut self, now: f32, settings: &InertiaSettings) {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(time, _)| *time <= now - settings.sample_window)
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn translation(events: &[ControlMessage]) -> Vec3 {
+        events
+            .iter()
+            .map(|event| match event {
+                ControlMessage::TranslateTarget(_, delta) => *delta,
+                _ => Vec3::ZERO,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_pan_glides_and_stops() {
+        let settings = InertiaSettings::default();
+        let camera = Entity::PLACEHOLDER;
+        let mut inertia = Inertia::default();
+
+        let mut now = 0.0;
+        for _ in 0..10 {
+            now += FRAME;
+            inertia.record(
+                now,
+                &[ControlMessage::TranslateTarget(camera, Vec3::X * FRAME)],
+                &settings,
+            );
+        }
+
+        // Released while moving at one unit per second
+        let first = translation(&inertia.glide(camera, FRAME, &settings));
+        assert!((first.x / FRAME - 1.0).abs() < 0.1);
+
+        let mut frames = 1;
+        while inertia.is_active() {
+            let next = translation(&inertia.glide(camera, FRAME, &settings));
+            assert!(next.x > 0.0 && next.x < first.x);
+            frames += 1;
+        }
+        assert!(frames < 10 * 60);
+    }
+
+    #[test]
+    fn test_input_stops_glide() {
+        let settings = InertiaSettings::default();
+        let camera = Entity::PLACEHOLDER;
+        let mut inertia = Inertia::default();
+
+        inertia.record(FRAME, &[ControlMessage::Orbit(camera, Vec2::X)], &settings);
+        assert!(!inertia.glide(camera, FRAME, &settings).is_empty());
+
+        // Holding the camera still during a drag
+        inertia.record(3.0 * FRAME, &[], &settings);
+        assert!(inertia.glide.is_none());
+    }
+
+    #[test]
+    fn test_single_scroll_does_not_glide() {
+        let settings = InertiaSettings::default();
+        let camera = Entity::PLACEHOLDER;
+        let mut inertia = Inertia::default();
+
+        inertia.record(
+            FRAME,
+            &[ControlMessage::Zoom {
+                camera,
+                zoom_scalar: 0.9,
+                zoom_target: Vec3::ZERO,
+            }],
+            &settings,
+        );
+
+        assert!(inertia.glide(camera, FRAME, &settings).is_empty());
+        assert!(!inertia.is_active());
+    }
+}
@@ -0,0 +1,178 @@
+use bevy_app::prelude::*;
+use bevy_camera::Projection;
+use bevy_ecs::prelude::*;
+use bevy_input::{ButtonInput, keyboard::KeyCode};
+use bevy_math::Vec2;
+use bevy_time::Time;
+
+use super::{ControlMessage, ControllerSettings, FlyTo, PointerCameras, view_aligned_translation};
+use crate::{CameraChange, LookTransform, inputs::Inputs};
+
+/// How long turning the camera back with a reset binding takes, in seconds
+const RESET_DURATION: f32 = 0.5;
+
+pub(super) struct KeyboardController;
+
+impl Plugin for KeyboardController {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (keyboard_control, reset_view).in_set(CameraChange::Before),
+        );
+    }
+}
+
+/// Pans, rotates and zooms the camera under the cursor while keys are held
+fn keyboard_control(
+    cameras: Query<(&LookTransform, Option<&Projection>)>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut camera_writer: MessageWriter<ControlMessage>,
+) {
+    if keys.get_pressed().len() == 0 {
+        return;
+    }
+
+    let Some(camera) = pointer_cameras.under_cursor_or_top() else {
+        return;
+    };
+
+    let Ok((look_transform, projection)) = cameras.get(camera) else {
+        return;
+    };
+
+    let settings = settings.get(camera);
+    let bindings = &settings.keys;
+    let delta_seconds = time.delta_secs();
+
+    let axis = |positive: &[KeyCode], negative: &[KeyCode]| {
+        let pressed = |codes: &[KeyCode]| keys.any_pressed(codes.iter().copied()) as i8;
+        (pressed(positive) - pressed(negative)) as f32
+    };
+
+    let pan = Vec2::new(
+        axis(&bindings.pan_right, &bindings.pan_left),
+        axis(&bindings.pan_forward, &bindings.pan_back),
+    );
+
+    if pan != Vec2::ZERO {
+        camera_writer.write(ControlMessage::TranslateTarget(
+            camera,
+            view_aligned_translation(
+                look_transform,
+                projection,
+                pan.normalize() * settings.keyboard_pan_speed * delta_seconds,
+            ),
+        ));
+    }
+
+    let orbit = Vec2::new(
+        axis(&bindings.yaw_right, &bindings.yaw_left),
+        axis(&bindings.pitch_up, &bindings.pitch_down),
+    );
+
+    if orbit != Vec2::ZERO {
+        camera_writer.write(ControlMessage::Orbit(
+            camera,
+            orbit * settings.keyboard_rotation_speed * delta_seconds,
+        ));
+    }
+
+    let zoom = axis(&bindings.zoom_out, &bindings.zoom_in);
+
+    if zoom != 0.0 {
+        camera_writer.write(ControlMessage::Zoom {
+            camera,
+            zoom_scalar: settings.keyboard_zoom_speed.powf(zoom * delta_seconds),
+            zoom_target: look_transform.target,
+        });
+    }
+}
+
+/// Turns the camera under the cursor to face north and look down when a reset binding is pressed
+fn reset_view(
+    cam_q: Query<&LookTransform>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    inputs: Inputs,
+    mut fly_to_writer: MessageWriter<FlyTo>,
+) {
+    let Some(camera) = pointer_cameras.under_cursor_or_top() else {
+        return;
+    };
+
+    let settings = settings.get(camera);
+    if !inputs.binding_just_pressed(&settings.buttons.reset) {
+        return;
+    }
+
+    let Ok(look_transform) = cam_q.get(camera) else {
+        return;
+    };
+
+    fly_to_writer.write(
+        FlyTo::new(camera, look_transform.target)
+            .with_yaw(0.0)
+            .with_pitch(settings.maximum_pitch)
+            .with_duration(RESET_DURATION)
+            .without_arc(),
+    );
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_camera::Camera;
+    use bevy_math::Vec3;
+    use bevy_window::{PrimaryWindow, Window};
+    use core::time::Duration;
+
+    use crate::{
+        CameraController,
+        controller::{control_system, tests::control_app},
+    };
+
+    #[test]
+    fn test_keys_pan_forward_and_zoom_in() {
+        let mut app = control_app(Duration::from_millis(500));
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.add_systems(PreUpdate, keyboard_control.before(control_system));
+
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera::default(),
+                CameraController::default(),
+                look_transform,
+                Projection::default(),
+            ))
+            .id();
+
+        // The first update starts the clock
+        app.update();
+
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::Equal);
+        app.update();
+
+        let new_look_transform = app.world().get::<LookTransform>(camera).unwrap();
+
+        // Forward is towards negative Z when looking from positive Z
+        assert!(new_look_transform.target.z < 0.0);
+        assert!(new_look_transform.target.x.abs() < 1e-5);
+        assert!(new_look_transform.radius() < look_transform.radius());
+    }
+}
@@ -1,40 +1,135 @@
+mod bounds;
+mod collision;
+mod fit;
+mod fly_to;
+mod gamepad;
+mod ground;
+mod inertia;
+mod keyboard;
 mod mouse;
 mod mouse_input;
 mod resources;
 mod touch;
 mod touch_inputs;
+mod trackpad;
+mod zoom_animation;
 
 use std::f32::consts::PI;
 
 use bevy_app::{App, Plugin, PreUpdate};
-use bevy_camera::{Camera, ViewportConversionError};
-use bevy_ecs::prelude::*;
+use bevy_camera::{Camera, Projection, RenderTarget, ViewportConversionError};
+use bevy_ecs::{
+    entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+    query::QueryData,
+    system::SystemParam,
+};
 
-use bevy_math::{Ray3d, Vec2, Vec3};
+use bevy_math::{Quat, Ray3d, Rect, Vec2, Vec3};
+use bevy_picking::{hover::HoverMap, pointer::PointerId};
 use bevy_reflect::prelude::*;
-use bevy_transform::components::GlobalTransform;
-use bevy_window::Window;
+use bevy_time::Time;
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_window::{PrimaryWindow, Window, WindowRef};
 
-use crate::{CameraChange, LookTransform, inputs::InputButton, look_angles::LookAngles};
+use crate::{
+    CameraChange, LookTransform, Smoother,
+    inputs::{Binding, InputButton},
+    look_angles::LookAngles,
+};
+use inertia::{ControlWriter, Dragging, Inertia};
 
-pub use resources::CameraControllerButtons;
+pub use bounds::{BoundsShape, PanBounds};
+pub use collision::CameraCollision;
+pub use fit::{FitBounds, FitToBounds};
+pub use fly_to::{FlyTo, FlyToFinished};
+pub use gamepad::GamepadInputSettings;
+pub use ground::{FlatGround, Ground, GroundQuery, Heightmap};
+pub use resources::{CameraControllerButtons, CameraControllerKeys, InertiaSettings, TrackpadMode};
+pub use touch_inputs::TouchInputSettings;
+pub use zoom_animation::ZoomAnimation;
 
-#[derive(Resource, Clone, Reflect)]
-#[reflect(Resource)]
+/// Settings for how the cameras are controlled.
+///
+/// Used as a resource for all cameras, insert it as a component on a camera to override the resource
+/// for that camera.
+#[derive(Resource, Component, Clone, Reflect)]
+#[reflect(Resource, Component)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct CameraControllerSettings {
     /// Enabled by default
     pub touch_enabled: bool,
     pub mouse_zoom_sensitivity_modifier: f32,
     pub mouse_rotation_sensitivity_modifier: f32,
-    pub touch_zoom_sensitivity_modifier: f32,
+    /// How strongly dragging with a [`CameraControllerButtons::zoom`] binding zooms, per pixel dragged
+    pub mouse_drag_zoom_sensitivity_modifier: f32,
+    /// In radians per line scrolled with a [`CameraControllerButtons::scroll_tilt`] binding held
+    pub scroll_tilt_sensitivity_modifier: f32,
+    /// In radians per line scrolled with a [`CameraControllerButtons::scroll_rotate`] binding held
+    pub scroll_rotation_sensitivity_modifier: f32,
+    /// The pinch zooms by the change in distance between the fingers to the power of this, `1.0`
+    /// keeps the ground under the fingers
+    pub touch_pinch_zoom_exponent: f32,
     pub touch_rotation_sensitivity_modifier: f32,
     pub touch_translation_sensitivity_modifier: f32,
+    /// How much of the view the keyboard pans per second, `1.0` pans the height of the view
+    pub keyboard_pan_speed: f32,
+    /// In radians per second
+    pub keyboard_rotation_speed: f32,
+    /// How many times closer, or further away, the keyboard zooms per second
+    pub keyboard_zoom_speed: f32,
+    /// How much of the view a fully tilted stick pans per second, `1.0` pans the height of the view
+    pub gamepad_pan_speed: f32,
+    /// In radians per second with a fully tilted stick
+    pub gamepad_rotation_speed: f32,
+    /// How many times closer, or further away, a fully pressed trigger zooms per second
+    pub gamepad_zoom_speed: f32,
     /// In radians
     pub minimum_pitch: f32,
+    /// In radians, can't be larger than [`LookAngles::MAX_PITCH`]
+    pub maximum_pitch: f32,
+    /// The `(minimum, maximum)` yaw in radians, `None` lets the camera orbit all the way around.
+    ///
+    /// A yaw of `0.0` is the camera looking along the negative Z axis.
+    pub yaw_range: Option<(f32, f32)>,
     pub minimum_zoom: f32,
     pub maximum_zoom: f32,
+    /// Smallest [`OrthographicProjection::scale`](bevy_camera::OrthographicProjection::scale) when zooming a camera with an orthographic projection
+    pub minimum_orthographic_scale: f32,
+    /// Largest [`OrthographicProjection::scale`](bevy_camera::OrthographicProjection::scale) when zooming a camera with an orthographic projection
+    pub maximum_orthographic_scale: f32,
+    /// How many times closer a double click, or one finger double tap, zooms. A two finger tap, or a
+    /// double click with [`CameraControllerButtons::double_click_zoom_out`] held, zooms out as much.
+    ///
+    /// Set to `1.0` to disable double click zooming.
+    pub double_click_zoom_factor: f32,
+    /// How long the double click zoom takes, in seconds
+    pub double_click_zoom_duration: f32,
+    /// Longest time between the clicks of a double click, in seconds
+    pub double_click_time: f32,
+    /// Zoom towards, and grab pan from, the closest `bevy_picking` hit under the pointer instead of the
+    /// ground, so that zooming onto a rooftop zooms towards the rooftop. Falls back to the ground when
+    /// nothing is hit.
+    ///
+    /// Needs a picking backend, like mesh picking, for the entities to hit. Pinches and double taps
+    /// don't have a single pointer and always use the ground. Disabled by default.
+    pub anchor_on_picking_hits: bool,
     /// Buttons to use when controlling the camera with a mouse (or some touchpads)
     pub buttons: CameraControllerButtons,
+    /// Keys to use when controlling the camera with a keyboard
+    pub keys: CameraControllerKeys,
+    /// Gliding after a pan, rotation or zoom is released
+    pub inertia: InertiaSettings,
+    /// Pan with two finger scrolling and use trackpad gestures, off by default
+    pub trackpad: TrackpadMode,
+    /// Rotate around the point under the pointer when the rotation starts, instead of around the
+    /// target. The point is on the ground, or the closest `bevy_picking` hit with
+    /// [`Self::anchor_on_picking_hits`]. Disabled by default.
+    pub orbit_around_pointer: bool,
 }
 
 impl Default for CameraControllerSettings {
@@ -43,26 +138,104 @@ impl Default for CameraControllerSettings {
             touch_enabled: true,
             mouse_zoom_sensitivity_modifier: 0.06,
             mouse_rotation_sensitivity_modifier: 0.00544,
+            mouse_drag_zoom_sensitivity_modifier: 0.00544,
+            scroll_tilt_sensitivity_modifier: 0.05,
+            scroll_rotation_sensitivity_modifier: 0.1,
             touch_rotation_sensitivity_modifier: 0.008,
-            touch_zoom_sensitivity_modifier: 0.008,
+            touch_pinch_zoom_exponent: 1.0,
             touch_translation_sensitivity_modifier: 0.02,
+            keyboard_pan_speed: 0.5,
+            keyboard_rotation_speed: 1.5,
+            keyboard_zoom_speed: 2.0,
+            gamepad_pan_speed: 0.75,
+            gamepad_rotation_speed: 2.0,
+            gamepad_zoom_speed: 3.0,
             minimum_pitch: 25.0 * PI / 180.0,
+            maximum_pitch: LookAngles::MAX_PITCH,
+            yaw_range: None,
             minimum_zoom: 1.5,
             maximum_zoom: 1_000.0,
+            minimum_orthographic_scale: 0.001,
+            maximum_orthographic_scale: 100.0,
+            double_click_zoom_factor: 2.0,
+            double_click_zoom_duration: 0.3,
+            double_click_time: 0.3,
+            anchor_on_picking_hits: false,
             buttons: CameraControllerButtons::default(),
+            keys: CameraControllerKeys::default(),
+            inertia: InertiaSettings::default(),
+            trackpad: TrackpadMode::default(),
+            orbit_around_pointer: false,
         }
     }
 }
 
 impl CameraControllerSettings {
+    /// Replaces the pan bindings with one that is pressed when only `btn` are pressed
     pub fn with_pan_button(mut self, btn: Vec<InputButton>) -> Self {
-        self.buttons.pan = btn;
+        self.buttons.pan = vec![Binding::exact(btn)];
         self
     }
+    /// Replaces the rotate bindings with one that is pressed when only `btn` are pressed
     pub fn with_rotate_button(mut self, btn: Vec<InputButton>) -> Self {
-        self.buttons.rotate = btn;
+        self.buttons.rotate = vec![Binding::exact(btn)];
         self
     }
+    pub fn with_pitch_range(mut self, minimum: f32, maximum: f32) -> Self {
+        self.minimum_pitch = minimum;
+        self.maximum_pitch = maximum;
+        self
+    }
+    pub fn with_yaw_range(mut self, minimum: f32, maximum: f32) -> Self {
+        self.yaw_range = Some((minimum, maximum));
+        self
+    }
+
+    /// The animated zoom towards `zoom_target` of a double click or tap
+    fn double_click_zoom(&self, zoom_target: Vec3, zoom_out: bool) -> Option<ZoomAnimation> {
+        if self.double_click_zoom_factor == 1.0 {
+            return None;
+        }
+
+        let zoom_scalar = if zoom_out {
+            self.double_click_zoom_factor
+        } else {
+            1.0 / self.double_click_zoom_factor
+        };
+
+        Some(ZoomAnimation::new(
+            zoom_scalar,
+            zoom_target,
+            self.double_click_zoom_duration,
+        ))
+    }
+
+    /// Keeps the pitch and yaw within the limits of these settings
+    pub fn limit_look_angles(&self, look_angles: &mut LookAngles) {
+        look_angles.set_pitch(
+            look_angles
+                .get_pitch()
+                .min(self.maximum_pitch)
+                .max(self.minimum_pitch),
+        );
+
+        if let Some((minimum, maximum)) = self.yaw_range {
+            look_angles.clamp_yaw(minimum, maximum);
+        }
+    }
+}
+
+/// The [`CameraControllerSettings`] of each camera, the component on a camera overrides the resource.
+#[derive(SystemParam)]
+pub(crate) struct ControllerSettings<'w, 's> {
+    global: Res<'w, CameraControllerSettings>,
+    overrides: Query<'w, 's, &'static CameraControllerSettings>,
+}
+
+impl<'w, 's> ControllerSettings<'w, 's> {
+    pub fn get(&self, camera: Entity) -> &CameraControllerSettings {
+        self.overrides.get(camera).unwrap_or(&self.global)
+    }
 }
 
 /// A 3rd person camera that orbits around the target.
@@ -71,8 +244,14 @@ impl CameraControllerSettings {
 pub struct CameraController {
     pub enabled: bool,
     pub pixels_per_line: f32,
+    /// How much of the remaining distance to the [`LookTransform`] the camera keeps after 1/60th of
+    /// a second, in the range `0.0..1.0`, values outside of it are clamped. The movement is the same
+    /// at any frame rate.
+    ///
+    /// Set to `0.0` to disable smoothing for this camera.
     pub smoothing_weight: f32,
-    /// The height of the grab plane that the camera moves along
+    /// The height of the grab plane that the camera moves along, follows the ground under the target
+    /// if the camera has a [`Ground`]
     pub grab_height: f32,
 }
 
@@ -101,15 +280,36 @@ impl bevy_tweening::Lens<CameraController> for GrabHeightLens {
     }
 }
 
-#[derive(Message)]
+/// Moves a [`CameraController`] camera, every message targets a specific camera entity.
+#[derive(Message, Debug, Clone, Copy)]
 pub enum ControlMessage {
-    Orbit(Vec2),
+    Orbit(Entity, Vec2),
     /// Translation Delta
-    TranslateTarget(Vec3),
+    TranslateTarget(Entity, Vec3),
     Zoom {
+        camera: Entity,
         zoom_scalar: f32,
         zoom_target: Vec3,
     },
+    /// Like [`ControlMessage::Orbit`], but the eye and target rotate around `pivot` so that it stays in
+    /// the same place on screen. The target is then moved along the view back to the grab plane.
+    OrbitAround {
+        camera: Entity,
+        delta: Vec2,
+        pivot: Vec3,
+    },
+}
+
+impl ControlMessage {
+    /// The camera entity that this message controls
+    pub fn camera(&self) -> Entity {
+        match self {
+            ControlMessage::Orbit(camera, _) => *camera,
+            ControlMessage::TranslateTarget(camera, _) => *camera,
+            ControlMessage::Zoom { camera, .. } => *camera,
+            ControlMessage::OrbitAround { camera, .. } => *camera,
+        }
+    }
 }
 
 pub(crate) struct CameraControllerPlugin;
@@ -118,54 +318,210 @@ impl Plugin for CameraControllerPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<CameraController>();
         app.register_type::<CameraControllerSettings>();
+        app.register_type::<PanBounds>();
+        app.register_type::<ZoomAnimation>();
         app.init_resource::<CameraControllerSettings>();
 
         app.add_message::<ControlMessage>();
+        app.add_message::<Dragging>();
+        app.add_message::<FlyTo>();
+        app.add_message::<FitToBounds>();
+        app.add_message::<FlyToFinished>();
 
         app.add_plugins(mouse::MouseController);
+        app.add_plugins(keyboard::KeyboardController);
+        app.add_plugins(gamepad::GamepadController);
         app.add_plugins(touch::TouchInputPlugin);
+        app.add_plugins(trackpad::TrackpadController);
 
         app.add_systems(
             PreUpdate,
             (
-                control_system.run_if(on_message::<ControlMessage>),
+                fit::fit_to_bounds,
+                fly_to::start_flights,
+                control_system,
+                fly_to::fly,
+                bounds::spring_back,
+                ground::follow_ground,
                 update_height,
+                limit_look_angles,
             )
                 .chain()
                 .after(CameraChange::Before)
                 .before(super::look_transform_system),
         );
+        app.add_systems(
+            PreUpdate,
+            collision::collide
+                .after(super::look_transform_system)
+                .before(CameraChange::After),
+        );
     }
 }
 
 fn update_height(
-    camera: Single<(&mut LookTransform, &CameraController), Changed<CameraController>>,
+    mut cameras: Query<(&mut LookTransform, &CameraController), Changed<CameraController>>,
 ) {
-    let (mut transform, controller) = camera.into_inner();
+    for (mut transform, controller) in cameras.iter_mut() {
+        if !controller.enabled {
+            continue;
+        }
+
+        let y_diff = controller.grab_height - transform.target.y;
+        transform.target.y = controller.grab_height;
 
-    if !controller.enabled {
-        return;
+        transform.eye.y += y_diff;
     }
+}
 
-    let y_diff = controller.grab_height - transform.target.y;
-    transform.target.y = controller.grab_height;
+/// Keeps cameras that are moved by other means than [`ControlMessage`], like tweens, within the
+/// pitch and yaw limits of their [`CameraControllerSettings`]
+fn limit_look_angles(
+    mut cameras: Query<
+        (
+            &mut LookTransform,
+            &CameraController,
+            Option<&CameraControllerSettings>,
+        ),
+        Changed<LookTransform>,
+    >,
+    global_settings: Res<CameraControllerSettings>,
+) {
+    for (mut transform, controller, settings) in cameras.iter_mut() {
+        if !controller.enabled {
+            continue;
+        }
 
-    transform.eye.y += y_diff;
+        let Some(look_direction) = transform.look_direction() else {
+            continue;
+        };
+
+        let settings = settings.unwrap_or(&global_settings);
+
+        let look_angles = LookAngles::from_vector(-look_direction);
+        let mut limited_angles = look_angles;
+        settings.limit_look_angles(&mut limited_angles);
+
+        if limited_angles != look_angles {
+            let radius = transform.radius();
+            transform.eye = transform.target + radius * limited_angles.unit_vector();
+        }
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct ControlledCamera {
+    entity: Entity,
+    look_transform: &'static mut LookTransform,
+    controller: &'static CameraController,
+    smoother: Option<&'static mut Smoother>,
+    projection: Option<&'static mut Projection>,
+    settings: Option<&'static CameraControllerSettings>,
+    bounds: Option<&'static PanBounds>,
+    zoom_animation: Option<&'static mut ZoomAnimation>,
+    flying: Has<fly_to::Flight>,
 }
 
 fn control_system(
+    mut commands: Commands,
     mut events: MessageReader<ControlMessage>,
-    camera: Single<(&mut LookTransform, &CameraController)>,
-    settings: Res<CameraControllerSettings>,
+    mut dragging: MessageReader<Dragging>,
+    mut cameras: Query<ControlledCamera>,
+    global_settings: Res<CameraControllerSettings>,
+    time: Res<Time>,
+    mut inertia: Local<EntityHashMap<Inertia>>,
 ) {
-    let (mut transform, controller) = camera.into_inner();
+    let mut camera_events = EntityHashMap::<Vec<ControlMessage>>::default();
+    for event in events.read() {
+        camera_events
+            .entry(event.camera())
+            .or_default()
+            .push(*event);
+    }
+
+    let dragged_cameras = dragging
+        .read()
+        .map(|Dragging(camera)| *camera)
+        .collect::<EntityHashSet>();
 
-    if !controller.enabled {
-        // Read all events to mark them as read
-        for _ev in events.read() {}
-        return;
+    // Cameras without input this frame can still be gliding or animating
+    for camera in inertia.keys().chain(&dragged_cameras) {
+        camera_events.entry(*camera).or_default();
+    }
+    for camera in cameras
+        .iter()
+        .filter(|camera| camera.zoom_animation.is_some())
+    {
+        camera_events.entry(camera.entity).or_default();
     }
 
+    for (entity, mut events) in camera_events {
+        let Ok(mut camera) = cameras.get_mut(entity) else {
+            inertia.remove(&entity);
+            continue;
+        };
+
+        let inertia_settings = camera.settings.unwrap_or(&global_settings).inertia;
+        // A flight sets the transform directly, gliding would only make it stop early
+        if !camera.controller.enabled || !inertia_settings.enabled || camera.flying {
+            inertia.remove(&entity);
+        } else if dragged_cameras.contains(&entity) {
+            inertia.entry(entity).or_default().record(
+                time.elapsed_secs(),
+                &events,
+                &inertia_settings,
+            );
+        } else if events.is_empty() {
+            events = inertia.entry(entity).or_default().glide(
+                entity,
+                time.delta_secs(),
+                &inertia_settings,
+            );
+        } else {
+            // Any other input stops the glide without starting a new one
+            inertia.remove(&entity);
+        }
+
+        if !camera.controller.enabled {
+            continue;
+        }
+
+        // Animations are not part of the inertia, they end at the zoom they were made for
+        if let Some(animation) = &mut camera.zoom_animation {
+            events.extend(animation.advance(entity, time.delta_secs()));
+
+            if animation.is_finished() {
+                commands.entity(entity).remove::<ZoomAnimation>();
+            }
+        }
+
+        if events.is_empty() {
+            continue;
+        }
+
+        control_camera(&events, camera, &global_settings);
+    }
+
+    inertia.retain(|_, inertia| inertia.is_active());
+}
+
+fn control_camera(
+    events: &[ControlMessage],
+    camera: ControlledCameraItem,
+    global_settings: &CameraControllerSettings,
+) {
+    let ControlledCameraItem {
+        look_transform: mut transform,
+        controller,
+        mut smoother,
+        projection,
+        settings,
+        bounds,
+        ..
+    } = camera;
+    let settings = settings.unwrap_or(global_settings);
+
     let mut look_angles = LookAngles::from_vector(
         -transform
             .look_direction()
@@ -173,46 +529,133 @@ fn control_system(
     );
 
     let mut radius_scalar = 1.0;
-    let radius = transform.radius();
+    let mut radius = transform.radius();
 
-    for event in events.read() {
+    // Orthographic cameras zoom by scaling the projection instead of moving the eye
+    let mut orthographic = projection.and_then(|projection| {
+        projection.filter_map_unchanged(|projection| match projection {
+            Projection::Orthographic(orthographic) => Some(orthographic),
+            _ => None,
+        })
+    });
+    let orthographic_scale = orthographic.as_ref().map(|orthographic| orthographic.scale);
+
+    // Moves from one target to another, with the bounds resisting the move
+    let constrain_target = |from: Vec3, to: Vec3| {
+        bounds.map_or(to, |bounds: &PanBounds| {
+            bounds.constrain_translation(from, to - from)
+        })
+    };
+
+    for event in events {
         match event {
-            ControlMessage::Orbit(delta) => {
+            ControlMessage::Orbit(_, delta) => {
                 look_angles.add_yaw(-delta.x);
                 look_angles.add_pitch(delta.y);
 
-                if look_angles.get_pitch() < settings.minimum_pitch {
-                    look_angles.set_pitch(settings.minimum_pitch)
+                settings.limit_look_angles(&mut look_angles);
+            }
+            ControlMessage::OrbitAround { delta, pivot, .. } => {
+                let previous_angles = look_angles;
+                look_angles.add_yaw(-delta.x);
+                look_angles.add_pitch(delta.y);
+                settings.limit_look_angles(&mut look_angles);
+
+                // Earlier zooms scale the distance to the eye of perspective cameras, orthographic
+                // ones scale the projection instead
+                let distance_scalar = match orthographic_scale {
+                    Some(_) => 1.0,
+                    None => radius_scalar,
+                };
+
+                // Turn the whole view around the pivot, like turning the world under the camera
+                let rotation =
+                    view_rotation(look_angles) * view_rotation(previous_angles).inverse();
+                let eye =
+                    transform.target + distance_scalar * radius * previous_angles.unit_vector();
+                let eye = *pivot + rotation * (eye - *pivot);
+                let mut target = *pivot + rotation * (transform.target - *pivot);
+
+                let back = look_angles.unit_vector();
+                if back.y > f32::EPSILON {
+                    target -= back * (target.y - controller.grab_height) / back.y;
                 }
+                radius = eye.distance(target) / distance_scalar;
+
+                let target_delta = constrain_target(transform.target, target) - transform.target;
+                transform.target += target_delta;
             }
-            ControlMessage::TranslateTarget(delta) => {
-                transform.target += *delta;
+            ControlMessage::TranslateTarget(_, delta) => {
+                let target_delta = constrain_target(transform.target, transform.target + *delta)
+                    - transform.target;
+                transform.target += target_delta;
+
+                // Translations are not smoothed, grab pan needs to follow the pointer exactly
+                if let Some(smoother) = &mut smoother {
+                    smoother.translate(target_delta);
+                }
             }
             ControlMessage::Zoom {
                 zoom_scalar,
                 zoom_target,
+                ..
             } => {
-                radius_scalar *= zoom_scalar;
-
-                let new_radius = radius_scalar * radius;
-
-                if new_radius < settings.minimum_zoom || new_radius > settings.maximum_zoom {
+                // Zoom as far as the limits allow, so that the zoom target stays under the pointer
+                // at the limits too
+                let (current, minimum, maximum) = match orthographic_scale {
+                    Some(scale) => (
+                        radius_scalar * scale,
+                        settings.minimum_orthographic_scale,
+                        settings.maximum_orthographic_scale,
+                    ),
+                    None => (
+                        radius_scalar * radius,
+                        settings.minimum_zoom,
+                        settings.maximum_zoom,
+                    ),
+                };
+                let zoom_scalar = (current * zoom_scalar).min(maximum).max(minimum) / current;
+                if zoom_scalar == 1.0 || !zoom_scalar.is_finite() {
                     continue;
                 }
 
-                let mut dir = transform.target - *zoom_target;
-                dir.y = 0.0;
+                let target = anchored_zoom_target(
+                    transform.target.with_y(controller.grab_height),
+                    look_angles.unit_vector(),
+                    radius_scalar * radius,
+                    zoom_scalar,
+                    *zoom_target,
+                    orthographic_scale.is_some(),
+                );
+                radius_scalar *= zoom_scalar;
 
-                transform.target -= dir * (1. - *zoom_scalar);
+                let target_delta = constrain_target(transform.target, target) - transform.target;
+                transform.target += target_delta;
+
+                // The orthographic scale is not smoothed, so the target can't be either
+                // without the zoom target drifting away from the pointer
+                if let (Some(_), Some(smoother)) = (orthographic_scale, &mut smoother) {
+                    smoother.translate(target_delta);
+                }
             }
         }
     }
 
     look_angles.assert_not_looking_up();
 
-    let new_radius = (radius_scalar * radius)
-        .min(settings.maximum_zoom)
-        .max(settings.minimum_zoom);
+    let new_radius = match (&mut orthographic, orthographic_scale) {
+        (Some(orthographic), Some(scale)) => {
+            if radius_scalar != 1.0 {
+                orthographic.scale = (radius_scalar * scale)
+                    .min(settings.maximum_orthographic_scale)
+                    .max(settings.minimum_orthographic_scale);
+            }
+            radius
+        }
+        _ => (radius_scalar * radius)
+            .min(settings.maximum_zoom)
+            .max(settings.minimum_zoom),
+    };
 
     transform.target.y = controller.grab_height;
 
@@ -222,6 +665,90 @@ fn control_system(
     transform.eye.y = transform.eye.y.max(controller.grab_height + 0.1);
 }
 
+/// The target after zooming by `zoom_scalar` so that `zoom_target` stays on the same pixel on screen.
+///
+/// `back` points from `target`, on the grab plane, towards the eye which is `radius` away. The zoom
+/// scales the radius of perspective cameras and the projection of orthographic ones, the new target is
+/// on the grab plane too.
+fn anchored_zoom_target(
+    target: Vec3,
+    back: Vec3,
+    radius: f32,
+    zoom_scalar: f32,
+    zoom_target: Vec3,
+    orthographic: bool,
+) -> Vec3 {
+    let height = target.y;
+
+    if orthographic {
+        // Scale the distance to the zoom target across the view, the distance along the view doesn't
+        // change where it is on screen
+        let offset = target - zoom_target;
+        let across = offset - back * offset.dot(back);
+        let new_target = zoom_target + across * zoom_scalar;
+
+        if back.y <= f32::EPSILON {
+            return new_target;
+        }
+        return new_target - back * (new_target.y - height) / back.y;
+    }
+
+    // Slide the eye along the line through the zoom target, which keeps the zoom target on the same
+    // view ray, to where the eye is `zoom_scalar` times as high above the grab plane
+    let eye = target + back * radius;
+    let new_eye_height = height + zoom_scalar * (eye.y - height);
+    let along = if (eye.y - zoom_target.y).abs() > f32::EPSILON {
+        (new_eye_height - zoom_target.y) / (eye.y - zoom_target.y)
+    } else {
+        zoom_scalar
+    };
+    let new_eye = zoom_target + (eye - zoom_target) * along;
+
+    new_eye - back * radius * zoom_scalar
+}
+
+/// The rotation of a camera looking at its target from `look_angles`
+fn view_rotation(look_angles: LookAngles) -> Quat {
+    Transform::IDENTITY
+        .looking_to(-look_angles.unit_vector(), Vec3::Y)
+        .rotation
+}
+
+/// Converts a movement in view heights, with `x` to the right and `y` forward, to a translation along
+/// the XZ plane
+fn view_aligned_translation(
+    look_transform: &LookTransform,
+    projection: Option<&Projection>,
+    movement: Vec2,
+) -> Vec3 {
+    let look_direction = look_transform.look_direction().unwrap_or(Vec3::NEG_Z);
+    let forward = Vec3::new(look_direction.x, 0.0, look_direction.z).normalize_or_zero();
+    let right = forward.cross(Vec3::Y);
+
+    let radius = look_transform.radius();
+    let view_height = match projection {
+        Some(Projection::Perspective(perspective)) => 2.0 * radius * (perspective.fov / 2.0).tan(),
+        Some(Projection::Orthographic(orthographic)) => orthographic.area.height(),
+        _ => radius,
+    };
+
+    (forward * movement.y + right * movement.x) * view_height
+}
+
+/// The point on the ground, or grab plane, of a camera that is under `screen_position`
+fn screen_ground_point(
+    screen_position: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    window: &Window,
+    controller: &CameraController,
+    ground: Option<&Ground>,
+) -> Option<Vec3> {
+    let ray = ray_from_screenspace(screen_position, camera, camera_transform, window).ok()?;
+
+    ground::ground_point(ray, controller, ground)
+}
+
 fn ray_from_screenspace(
     cursor_pos_screen: Vec2,
     camera: &Camera,
@@ -232,7 +759,610 @@ fn ray_from_screenspace(
     if let Some(viewport) = &camera.viewport {
         viewport_pos -= viewport.physical_position.as_vec2() / window.scale_factor();
     }
-    camera
-        .viewport_to_world(camera_transform, viewport_pos)
-        .map(Ray3d::from)
+    camera.viewport_to_world(camera_transform, viewport_pos)
+}
+
+/// Finds the [`CameraController`] camera that a pointer on the primary window is over.
+#[derive(SystemParam)]
+pub(crate) struct PointerCameras<'w, 's> {
+    cameras: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Camera,
+            &'static CameraController,
+            Option<&'static Ground>,
+        ),
+    >,
+    zoom_animations: Query<'w, 's, (), With<ZoomAnimation>>,
+    primary_window: Query<'w, 's, (Entity, &'static Window), With<PrimaryWindow>>,
+    hover_map: Option<Res<'w, HoverMap>>,
+}
+
+impl<'w, 's> PointerCameras<'w, 's> {
+    pub fn window(&self) -> Option<&Window> {
+        self.primary_window.single().ok().map(|(_, window)| window)
+    }
+
+    pub fn window_entity(&self) -> Option<Entity> {
+        self.primary_window.single().ok().map(|(entity, _)| entity)
+    }
+
+    pub fn controller(&self, camera: Entity) -> Option<&CameraController> {
+        self.cameras
+            .get(camera)
+            .ok()
+            .map(|(_, _, controller, _)| controller)
+    }
+
+    pub fn ground(&self, camera: Entity) -> Option<&Ground> {
+        self.cameras
+            .get(camera)
+            .ok()
+            .and_then(|(_, _, _, ground)| ground)
+    }
+
+    /// If `camera` is playing a [`ZoomAnimation`], like the zoom of a double click
+    pub fn zoom_animating(&self, camera: Entity) -> bool {
+        self.zoom_animations.contains(camera)
+    }
+
+    /// The world position of the closest `bevy_picking` hit of `pointer` seen by `camera`, for
+    /// [`CameraControllerSettings::anchor_on_picking_hits`].
+    ///
+    /// Only hits with a position are used, the picking backend has to report it in world space like
+    /// the mesh and sprite picking backends do.
+    pub fn picking_hit(&self, pointer: PointerId, camera: Entity) -> Option<Vec3> {
+        self.hover_map
+            .as_ref()?
+            .get(&pointer)?
+            .values()
+            .filter(|hit| hit.camera == camera)
+            .filter_map(|hit| hit.position.map(|position| (hit.depth, position)))
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, position)| position)
+    }
+
+    /// Returns the camera under the cursor of the primary window.
+    ///
+    /// Falls back to the only camera if the cursor is outside the window and there is just one.
+    pub fn under_cursor(&self) -> Option<Entity> {
+        match self.window().and_then(Window::cursor_position) {
+            Some(cursor_position) => self.at_position(cursor_position),
+            None => self.cameras.single().ok().map(|(entity, ..)| entity),
+        }
+    }
+
+    /// Returns the camera under the cursor, or the top most camera if the cursor isn't over any camera.
+    pub fn under_cursor_or_top(&self) -> Option<Entity> {
+        self.under_cursor().or_else(|| {
+            let (window_entity, _) = self.primary_window.single().ok()?;
+
+            self.cameras
+                .iter()
+                .filter(|(_, camera, ..)| {
+                    camera.is_active && renders_to_window(camera, window_entity)
+                })
+                .max_by_key(|(_, camera, ..)| camera.order)
+                .map(|(entity, ..)| entity)
+        })
+    }
+
+    /// Returns the camera whose viewport contains the logical `screen_position` of the primary window.
+    /// When viewports overlap the camera with the highest order, the one rendered on top, is returned.
+    pub fn at_position(&self, screen_position: Vec2) -> Option<Entity> {
+        let (window_entity, window) = self.primary_window.single().ok()?;
+
+        self.cameras
+            .iter()
+            .filter(|(_, camera, ..)| {
+                camera.is_active
+                    && renders_to_window(camera, window_entity)
+                    && viewport_contains(camera, window, screen_position)
+            })
+            .max_by_key(|(_, camera, ..)| camera.order)
+            .map(|(entity, ..)| entity)
+    }
+}
+
+fn renders_to_window(camera: &Camera, window_entity: Entity) -> bool {
+    match &camera.target {
+        RenderTarget::Window(WindowRef::Primary) => true,
+        RenderTarget::Window(WindowRef::Entity(entity)) => *entity == window_entity,
+        _ => false,
+    }
+}
+
+/// Uses the same [`Camera::viewport`] handling as [`ray_from_screenspace`]
+fn viewport_contains(camera: &Camera, window: &Window, screen_position: Vec2) -> bool {
+    let Some(viewport) = &camera.viewport else {
+        return true;
+    };
+
+    let min = viewport.physical_position.as_vec2() / window.scale_factor();
+    let size = viewport.physical_size.as_vec2() / window.scale_factor();
+
+    Rect::from_corners(min, min + size).contains(screen_position)
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_camera::Viewport;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::UVec2;
+    use core::time::Duration;
+
+    use crate::test_utils::headless_app;
+
+    /// A headless app that controls its cameras with the [`ControlMessage`]s that are sent to it
+    pub(super) fn control_app(frame_time: Duration) -> App {
+        let mut app = headless_app(frame_time);
+        app.init_resource::<CameraControllerSettings>();
+        app.add_message::<ControlMessage>();
+        app.add_message::<Dragging>();
+        app.add_systems(PreUpdate, control_system);
+        app
+    }
+
+    #[test]
+    fn test_pointer_goes_to_camera_under_it() {
+        let mut world = World::new();
+        world.spawn((Window::default(), PrimaryWindow));
+
+        let main_camera = world
+            .spawn((Camera::default(), CameraController::default()))
+            .id();
+        let minimap_camera = world
+            .spawn((
+                Camera {
+                    order: 1,
+                    viewport: Some(Viewport {
+                        physical_position: UVec2::new(20, 20),
+                        physical_size: UVec2::new(300, 300),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                CameraController::default(),
+            ))
+            .id();
+
+        let (over_minimap, over_main) = world
+            .run_system_once(|pointer_cameras: PointerCameras| {
+                (
+                    pointer_cameras.at_position(Vec2::new(100.0, 100.0)),
+                    pointer_cameras.at_position(Vec2::new(600.0, 400.0)),
+                )
+            })
+            .unwrap();
+
+        assert_eq!(over_minimap, Some(minimap_camera));
+        assert_eq!(over_main, Some(main_camera));
+    }
+
+    #[test]
+    fn test_inactive_camera_gets_no_input() {
+        let mut world = World::new();
+        world.spawn((Window::default(), PrimaryWindow));
+
+        let main_camera = world
+            .spawn((Camera::default(), CameraController::default()))
+            .id();
+        world.spawn((
+            Camera {
+                order: 1,
+                is_active: false,
+                ..Default::default()
+            },
+            CameraController::default(),
+        ));
+
+        let camera = world
+            .run_system_once(|pointer_cameras: PointerCameras| {
+                pointer_cameras.at_position(Vec2::new(100.0, 100.0))
+            })
+            .unwrap();
+
+        assert_eq!(camera, Some(main_camera));
+    }
+
+    #[test]
+    fn test_closest_picking_hit_is_anchor() {
+        use bevy_picking::backend::HitData;
+
+        let mut world = World::new();
+
+        let camera = world
+            .spawn((Camera::default(), CameraController::default()))
+            .id();
+        let other_camera = world
+            .spawn((Camera::default(), CameraController::default()))
+            .id();
+
+        let rooftop = Vec3::new(1.0, 20.0, 1.0);
+        let hits = [
+            HitData::new(camera, 30.0, Some(Vec3::ZERO), None),
+            HitData::new(camera, 10.0, Some(rooftop), None),
+            HitData::new(other_camera, 5.0, Some(Vec3::ONE), None),
+            HitData::new(camera, 1.0, None, None),
+        ];
+        let mut hover_map = HoverMap::default();
+        hover_map.insert(
+            PointerId::Mouse,
+            hits.into_iter()
+                .map(|hit| (world.spawn_empty().id(), hit))
+                .collect(),
+        );
+        world.insert_resource(hover_map);
+
+        let (hit, touch_hit) = world
+            .run_system_once(move |pointer_cameras: PointerCameras| {
+                (
+                    pointer_cameras.picking_hit(PointerId::Mouse, camera),
+                    pointer_cameras.picking_hit(PointerId::Touch(0), camera),
+                )
+            })
+            .unwrap();
+
+        assert_eq!(hit, Some(rooftop));
+        assert_eq!(touch_hit, None);
+    }
+
+    #[test]
+    fn test_settings_component_overrides_resource() {
+        let mut app = control_app(Duration::ZERO);
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+
+        let global_camera = app
+            .world_mut()
+            .spawn((look_transform, CameraController::default()))
+            .id();
+        let override_camera = app
+            .world_mut()
+            .spawn((
+                look_transform,
+                CameraController::default(),
+                CameraControllerSettings {
+                    maximum_zoom: 20.0,
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        for camera in [global_camera, override_camera] {
+            app.world_mut().write_message(ControlMessage::Zoom {
+                camera,
+                zoom_scalar: 3.0,
+                zoom_target: Vec3::ZERO,
+            });
+        }
+        app.update();
+
+        let radius = |camera| app.world().get::<LookTransform>(camera).unwrap().radius();
+
+        assert!((radius(global_camera) - 3.0 * look_transform.radius()).abs() < 1e-3);
+        assert!((radius(override_camera) - 20.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_only_drags_glide() {
+        let mut app = control_app(Duration::from_secs_f64(1.0 / 60.0));
+
+        let mut settings = CameraControllerSettings::default();
+        settings.inertia.enabled = true;
+        let mut spawn_camera = || {
+            app.world_mut()
+                .spawn((
+                    LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y),
+                    CameraController::default(),
+                    settings.clone(),
+                ))
+                .id()
+        };
+        let dragged_camera = spawn_camera();
+        let keyboard_camera = spawn_camera();
+
+        for _ in 0..10 {
+            for camera in [dragged_camera, keyboard_camera] {
+                app.world_mut()
+                    .write_message(ControlMessage::TranslateTarget(camera, Vec3::X * 0.1));
+            }
+            app.world_mut().write_message(Dragging(dragged_camera));
+            app.update();
+        }
+
+        // A drag held still doesn't glide
+        let target = |app: &App, camera| app.world().get::<LookTransform>(camera).unwrap().target;
+        let released_at = target(&app, dragged_camera);
+        app.world_mut().write_message(Dragging(dragged_camera));
+        app.update();
+        assert_eq!(target(&app, dragged_camera), released_at);
+
+        let stopped_at = target(&app, keyboard_camera);
+        app.update();
+        app.update();
+
+        assert!(target(&app, dragged_camera).x > released_at.x);
+        assert_eq!(target(&app, keyboard_camera), stopped_at);
+    }
+
+    #[test]
+    fn test_orthographic_zoom_scales_projection() {
+        let mut app = control_app(Duration::ZERO);
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((
+                look_transform,
+                CameraController::default(),
+                Projection::Orthographic(bevy_camera::OrthographicProjection::default_3d()),
+            ))
+            .id();
+
+        let zoom_target = Vec3::new(4.0, 0.0, -2.0);
+        app.world_mut().write_message(ControlMessage::Zoom {
+            camera,
+            zoom_scalar: 0.5,
+            zoom_target,
+        });
+        app.update();
+
+        let Projection::Orthographic(orthographic) = app.world().get::<Projection>(camera).unwrap()
+        else {
+            unreachable!()
+        };
+        let new_look_transform = app.world().get::<LookTransform>(camera).unwrap();
+
+        assert!((orthographic.scale - 0.5).abs() < 1e-6);
+        assert!((new_look_transform.radius() - look_transform.radius()).abs() < 1e-4);
+        // Halfway to the zoom target keeps the zoom target in the same place on screen
+        assert!(
+            new_look_transform
+                .target
+                .abs_diff_eq(zoom_target / 2.0, 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_orbit_around_keeps_pivot_in_place() {
+        let mut app = control_app(Duration::ZERO);
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((look_transform, CameraController::default()))
+            .id();
+
+        let pivot = Vec3::new(6.0, 0.0, -3.0);
+        // Where the pivot is seen from the camera
+        let view_position = |look_transform: &LookTransform| {
+            let transform = Transform::from(*look_transform);
+            transform.rotation.inverse() * (pivot - transform.translation)
+        };
+
+        app.world_mut().write_message(ControlMessage::OrbitAround {
+            camera,
+            delta: Vec2::new(0.3, 0.2),
+            pivot,
+        });
+        app.update();
+
+        let new_look_transform = app.world().get::<LookTransform>(camera).unwrap();
+        let look_angles = LookAngles::from_vector(-new_look_transform.look_direction().unwrap());
+
+        assert!((look_angles.get_yaw() - -0.3).abs() < 1e-4);
+        assert!(new_look_transform.target.y.abs() < 1e-4);
+        assert!(
+            view_position(new_look_transform).abs_diff_eq(view_position(&look_transform), 1e-3)
+        );
+    }
+
+    #[test]
+    fn test_zoom_then_orbit_around_keeps_pivot_in_place() {
+        let mut app = control_app(Duration::ZERO);
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let camera = app
+            .world_mut()
+            .spawn((look_transform, CameraController::default()))
+            .id();
+
+        let screen = Screen::new(Projection::default(), Vec2::ZERO, Vec2::new(800.0, 600.0));
+        let pivot = Vec3::new(3.0, 0.0, -2.0);
+        let start = screen.pixel(&look_transform, pivot);
+
+        // Like scrolling and rotating in the same frame
+        app.world_mut().write_message(ControlMessage::Zoom {
+            camera,
+            zoom_scalar: 0.5,
+            zoom_target: pivot,
+        });
+        app.world_mut().write_message(ControlMessage::OrbitAround {
+            camera,
+            delta: Vec2::new(0.3, 0.2),
+            pivot,
+        });
+        app.update();
+
+        let new_look_transform = app.world().get::<LookTransform>(camera).unwrap();
+
+        assert!(screen.pixel(new_look_transform, pivot).distance(start) < 0.05);
+        assert!(new_look_transform.radius() < look_transform.radius());
+    }
+
+    /// The zoom target of the pointer at `pixel` in a `viewport` of `(offset, size)`, and how the
+    /// viewport shows a world point
+    struct Screen {
+        projection: Projection,
+        offset: Vec2,
+        size: Vec2,
+    }
+
+    impl Screen {
+        fn new(mut projection: Projection, offset: Vec2, size: Vec2) -> Self {
+            projection.update(size.x, size.y);
+
+            Self {
+                projection,
+                offset,
+                size,
+            }
+        }
+
+        fn ray(&self, look_transform: &LookTransform, pixel: Vec2) -> Ray3d {
+            let ndc = (pixel - self.offset) / self.size * 2.0 - 1.0;
+            let world_from_clip = Transform::from(*look_transform).to_matrix()
+                * self.projection.get_clip_from_view().inverse();
+            // Reversed depth, the near plane is at 1.0
+            let near = world_from_clip.project_point3(Vec3::new(ndc.x, -ndc.y, 1.0));
+            let far = world_from_clip.project_point3(Vec3::new(ndc.x, -ndc.y, 0.5));
+
+            Ray3d::new(near, bevy_math::Dir3::new(far - near).unwrap())
+        }
+
+        fn pixel(&self, look_transform: &LookTransform, point: Vec3) -> Vec2 {
+            let clip_from_world = self.projection.get_clip_from_view()
+                * Transform::from(*look_transform).to_matrix().inverse();
+            let ndc = clip_from_world.project_point3(point);
+
+            self.offset + (Vec2::new(ndc.x, -ndc.y) + 1.0) / 2.0 * self.size
+        }
+    }
+
+    #[test]
+    fn test_zoom_keeps_point_under_pointer() {
+        use bevy_camera::{OrthographicProjection, PerspectiveProjection};
+
+        let mut app = control_app(Duration::ZERO);
+        let camera = app
+            .world_mut()
+            .spawn((LookTransform::default(), CameraController::default()))
+            .id();
+
+        let mut projections = [0.5, 0.8, 1.3]
+            .map(|fov| {
+                Projection::Perspective(PerspectiveProjection {
+                    fov,
+                    ..Default::default()
+                })
+            })
+            .to_vec();
+        projections.push(Projection::Orthographic(OrthographicProjection {
+            scaling_mode: bevy_camera::ScalingMode::FixedVertical {
+                viewport_height: 20.0,
+            },
+            ..OrthographicProjection::default_3d()
+        }));
+        let viewports = [
+            (Vec2::ZERO, Vec2::new(800.0, 600.0)),
+            (Vec2::new(200.0, 100.0), Vec2::new(400.0, 300.0)),
+        ];
+
+        for projection in projections {
+            for pitch in [0.45, 0.8, 1.2, 1.5] {
+                for (offset, size) in viewports {
+                    for pointer in [
+                        Vec2::new(0.1, 0.2),
+                        Vec2::new(0.5, 0.5),
+                        Vec2::new(0.9, 0.95),
+                    ] {
+                        for zoom_scalar in [0.5, 0.9, 1.3] {
+                            // A point on the grab plane, and one above it like a picked rooftop
+                            for elevated in [false, true] {
+                                let mut look_angles = LookAngles::default();
+                                look_angles.set_yaw(0.3);
+                                look_angles.set_pitch(pitch);
+                                let look_transform = LookTransform::new(
+                                    Vec3::new(2.0, 0.0, -1.0) + 20.0 * look_angles.unit_vector(),
+                                    Vec3::new(2.0, 0.0, -1.0),
+                                    Vec3::Y,
+                                );
+
+                                let screen = Screen::new(projection.clone(), offset, size);
+                                let pixel = offset + pointer * size;
+                                let ray = screen.ray(&look_transform, pixel);
+                                let ground = ray
+                                    .intersect_plane(
+                                        Vec3::ZERO,
+                                        bevy_math::primitives::InfinitePlane3d::new(Vec3::Y),
+                                    )
+                                    .unwrap();
+                                let distance = if elevated { ground * 0.8 } else { ground };
+                                let zoom_target = ray.get_point(distance);
+
+                                app.world_mut()
+                                    .entity_mut(camera)
+                                    .insert((look_transform, screen.projection.clone()));
+                                app.world_mut().write_message(ControlMessage::Zoom {
+                                    camera,
+                                    zoom_scalar,
+                                    zoom_target,
+                                });
+                                app.update();
+
+                                let zoomed = app.world().get::<LookTransform>(camera).unwrap();
+                                let zoomed_screen = Screen::new(
+                                    app.world().get::<Projection>(camera).unwrap().clone(),
+                                    offset,
+                                    size,
+                                );
+                                let zoomed_pixel = zoomed_screen.pixel(zoomed, zoom_target);
+
+                                assert!(
+                                    zoomed_pixel.distance(pixel) < 0.05,
+                                    "{pixel} moved to {zoomed_pixel} with pitch {pitch}, zoom {zoom_scalar} and {:?}",
+                                    screen.projection
+                                );
+                                assert!(zoomed.target.y.abs() < 1e-4);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_look_angles_are_limited_outside_control_system() {
+        let mut app = App::new();
+        app.init_resource::<CameraControllerSettings>();
+        app.add_systems(PreUpdate, limit_look_angles);
+
+        let camera = app
+            .world_mut()
+            .spawn((
+                LookTransform::default(),
+                CameraController::default(),
+                CameraControllerSettings::default()
+                    .with_pitch_range(0.2, 1.0)
+                    .with_yaw_range(-0.5, 0.5),
+            ))
+            .id();
+
+        // Like a tween would, move the eye above and behind the target, closer to the maximum yaw
+        app.world_mut()
+            .get_mut::<LookTransform>(camera)
+            .unwrap()
+            .eye = Vec3::new(0.05, 10.0, -0.1);
+        app.update();
+
+        let look_transform = app.world().get::<LookTransform>(camera).unwrap();
+        let look_angles = LookAngles::from_vector(-look_transform.look_direction().unwrap());
+
+        assert!((look_angles.get_pitch() - 1.0).abs() < 1e-4);
+        assert!((look_angles.get_yaw() - 0.5).abs() < 1e-4);
+        assert!((look_transform.radius() - 10.0).abs() < 1e-2);
+    }
 }
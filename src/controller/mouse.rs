@@ -1,30 +1,44 @@
 use bevy_app::prelude::*;
 use bevy_camera::Camera;
 use bevy_ecs::prelude::*;
+use bevy_input::mouse::MouseButton;
 use bevy_log::warn;
-use bevy_math::{Dir3, Ray3d, Vec3, primitives::InfinitePlane3d};
+use bevy_math::{Ray3d, Vec2, Vec3};
 use bevy_picking::{
     backend::ray::{RayId, RayMap},
     pointer::PointerId,
 };
 use bevy_platform::collections::HashMap;
+use bevy_time::{Real, Time};
 use bevy_transform::components::GlobalTransform;
-use bevy_window::{CursorIcon, PrimaryWindow, SystemCursorIcon, Window};
-
-use super::ray_from_screenspace;
+use bevy_window::{CursorIcon, SystemCursorIcon};
 
 use super::{
-    CameraController, CameraControllerSettings, ControlMessage, mouse_input::MouseKeyboardInputs,
+    CameraController, ControlMessage, ControlWriter, ControllerSettings, Ground, PointerCameras,
+    ground::{ground_point, plane_point},
+    mouse_input::MouseKeyboardInputs,
+    ray_from_screenspace,
+    resources::{DragAction, ScrollAction},
+    screen_ground_point,
 };
 use crate::{CameraChange, LookTransform, inputs::Inputs};
 
+/// Furthest the cursor can move between the clicks of a double click, in logical pixels
+const DOUBLE_CLICK_DISTANCE: f32 = 5.0;
+
 pub(super) struct MouseController;
 
 impl Plugin for MouseController {
     fn build(&self, app: &mut App) {
         app.add_systems(
             PreUpdate,
-            (zoom_orbit_camera, rotate_orbit_camera, grab_pan)
+            (
+                zoom_orbit_camera,
+                double_click_zoom,
+                rotate_orbit_camera,
+                tilt_zoom_camera,
+                grab_pan,
+            )
                 .chain()
                 .in_set(CameraChange::Before),
         );
@@ -33,49 +47,180 @@ impl Plugin for MouseController {
 
 /// Handles the rotation of the camera
 fn rotate_orbit_camera(
-    settings: Res<CameraControllerSettings>,
-    mut camera_writer: MessageWriter<ControlMessage>,
+    cam_q: Query<(
+        &CameraController,
+        &Camera,
+        &GlobalTransform,
+        Option<&Ground>,
+    )>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    mut camera_writer: ControlWriter,
     mut mouse_inputs: MouseKeyboardInputs,
+    mut rotating_camera: Local<Option<(Entity, Option<Vec3>)>>,
 ) {
-    let Some(rotation_move) = mouse_inputs
-        .mouse_drag(&settings.buttons.rotate)
-        .or_else(|| {
-            settings
-                .buttons
-                .rotate_alt
-                .as_ref()
-                .map(|alt| mouse_inputs.mouse_drag(alt))
-                .flatten()
-        })
+    // Keep rotating the same camera even if the cursor leaves its viewport
+    let Some(camera) = rotating_camera
+        .map(|(camera, _)| camera)
+        .or_else(|| pointer_cameras.under_cursor())
     else {
         return;
     };
 
-    camera_writer.write(ControlMessage::Orbit(
-        rotation_move * settings.mouse_rotation_sensitivity_modifier,
-    ));
+    let settings = settings.get(camera);
+    if settings.buttons.drag_action(&mouse_inputs) != Some(DragAction::Rotate) {
+        *rotating_camera = None;
+        return;
+    }
+
+    // The pivot is found when the rotation starts and kept until it ends
+    let pivot = match *rotating_camera {
+        Some((_, pivot)) => pivot,
+        None if settings.orbit_around_pointer => {
+            let picked = settings
+                .anchor_on_picking_hits
+                .then(|| pointer_cameras.picking_hit(PointerId::Mouse, camera))
+                .flatten();
+
+            picked.or_else(|| {
+                let window = pointer_cameras.window()?;
+                let cursor_position = window.cursor_position()?;
+                let (controller, camera, camera_gt, ground) = cam_q.get(camera).ok()?;
+                screen_ground_point(
+                    cursor_position,
+                    camera,
+                    camera_gt,
+                    window,
+                    controller,
+                    ground,
+                )
+            })
+        }
+        None => None,
+    };
+    *rotating_camera = Some((camera, pivot));
+    camera_writer.drag(camera);
+
+    let delta = mouse_inputs.mouse_motion() * settings.mouse_rotation_sensitivity_modifier;
+    if delta == Vec2::ZERO {
+        return;
+    }
+
+    camera_writer.write(match pivot {
+        Some(pivot) => ControlMessage::OrbitAround {
+            camera,
+            delta,
+            pivot,
+        },
+        None => ControlMessage::Orbit(camera, delta),
+    });
+}
+
+/// Only changes the pitch while the tilt buttons are dragged, and zooms towards the target while the
+/// zoom buttons are dragged. Dragging up tilts towards the horizon and zooms in.
+fn tilt_zoom_camera(
+    cam_q: Query<&LookTransform>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    mut camera_writer: ControlWriter,
+    mut mouse_inputs: MouseKeyboardInputs,
+    mut dragging_camera: Local<Option<Entity>>,
+) {
+    // Keep dragging the same camera even if the cursor leaves its viewport
+    let Some(camera) = dragging_camera.or_else(|| pointer_cameras.under_cursor()) else {
+        return;
+    };
+
+    let settings = settings.get(camera);
+    let action = settings.buttons.drag_action(&mouse_inputs);
+    if !matches!(action, Some(DragAction::Tilt | DragAction::Zoom)) {
+        *dragging_camera = None;
+        return;
+    }
+    *dragging_camera = Some(camera);
+    camera_writer.drag(camera);
+
+    let dragged = mouse_inputs.mouse_motion().y;
+    if dragged == 0.0 {
+        return;
+    }
+
+    if action == Some(DragAction::Tilt) {
+        camera_writer.write(ControlMessage::Orbit(
+            camera,
+            Vec2::new(0.0, dragged * settings.mouse_rotation_sensitivity_modifier),
+        ));
+    } else if let Ok(look_transform) = cam_q.get(camera) {
+        camera_writer.write(ControlMessage::Zoom {
+            camera,
+            zoom_scalar: (dragged * settings.mouse_drag_zoom_sensitivity_modifier).exp(),
+            zoom_target: look_transform.target,
+        });
+    }
 }
 
 /// Handles the zooming of the orbital camera
 fn zoom_orbit_camera(
-    cam_q: Single<(&CameraController, &Camera, &GlobalTransform, &LookTransform)>,
-    settings: Res<CameraControllerSettings>,
-    main_window: Single<&Window, With<PrimaryWindow>>,
+    cam_q: Query<(
+        &CameraController,
+        &Camera,
+        &GlobalTransform,
+        &LookTransform,
+        Option<&Ground>,
+    )>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
     mut mouse_inputs: MouseKeyboardInputs,
     mut camera_writer: MessageWriter<ControlMessage>,
 ) {
-    let (controller, camera, camera_gt, camera_lt) = cam_q.into_inner();
-    let window = main_window.into_inner();
+    let Some((camera_entity, (controller, camera, camera_gt, camera_lt, ground))) = pointer_cameras
+        .under_cursor()
+        .and_then(|entity| cam_q.get(entity).ok().map(|camera| (entity, camera)))
+    else {
+        mouse_inputs.clear_scroll();
+        return;
+    };
+
+    let settings = settings.get(camera_entity);
+
+    // Tilt or rotate instead of zooming while a scroll modifier is held
+    if let Some(action) = settings.buttons.scroll_action(&mouse_inputs) {
+        let Some(lines) = mouse_inputs.scroll_lines(controller.pixels_per_line, settings.trackpad)
+        else {
+            return;
+        };
+
+        // Some platforms turn the wheel into horizontal scrolling while Shift is held
+        let scrolled = lines.x + lines.y;
+        let delta = match action {
+            ScrollAction::Tilt => {
+                Vec2::new(0.0, -scrolled * settings.scroll_tilt_sensitivity_modifier)
+            }
+            ScrollAction::Rotate => Vec2::new(
+                scrolled * settings.scroll_rotation_sensitivity_modifier,
+                0.0,
+            ),
+        };
+        camera_writer.write(ControlMessage::Orbit(camera_entity, delta));
+        return;
+    }
 
     let scroll_sensitivity = settings.mouse_zoom_sensitivity_modifier;
 
-    let Some(scalar) = mouse_inputs.scroll_scalar(controller.pixels_per_line, scroll_sensitivity)
-    else {
+    let Some(scalar) = mouse_inputs.scroll_scalar(
+        controller.pixels_per_line,
+        scroll_sensitivity,
+        settings.trackpad,
+    ) else {
         return;
     };
 
-    let Some(mouse_pos) = window.cursor_position() else {
+    let Some((window, mouse_pos)) = pointer_cameras
+        .window()
+        .and_then(|window| window.cursor_position().map(|pos| (window, pos)))
+    else {
         camera_writer.write(ControlMessage::Zoom {
+            camera: camera_entity,
             zoom_scalar: scalar,
             zoom_target: camera_lt.target,
         });
@@ -85,6 +230,7 @@ fn zoom_orbit_camera(
 
     let Ok(ray) = ray_from_screenspace(mouse_pos, camera, camera_gt, window) else {
         camera_writer.write(ControlMessage::Zoom {
+            camera: camera_entity,
             zoom_scalar: scalar,
             zoom_target: camera_lt.target,
         });
@@ -92,47 +238,154 @@ fn zoom_orbit_camera(
         return;
     };
 
-    let Some(target_distance) = ray.intersect_plane(
-        Vec3::Y * controller.grab_height,
-        InfinitePlane3d { normal: Dir3::Y },
-    ) else {
+    let picked = settings
+        .anchor_on_picking_hits
+        .then(|| pointer_cameras.picking_hit(PointerId::Mouse, camera_entity))
+        .flatten();
+    let Some(target) = picked.or_else(|| ground_point(ray, controller, ground)) else {
         return;
     };
 
-    let target = ray.get_point(target_distance);
-
     camera_writer.write(ControlMessage::Zoom {
+        camera: camera_entity,
         zoom_scalar: scalar,
         zoom_target: target,
     });
 }
 
+/// Zooms towards the point under the cursor on a double click
+fn double_click_zoom(
+    mut commands: Commands,
+    cam_q: Query<(
+        &CameraController,
+        &Camera,
+        &GlobalTransform,
+        Option<&Ground>,
+    )>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    inputs: Inputs,
+    time: Res<Time<Real>>,
+    mut last_click: Local<Option<(f32, Vec2)>>,
+) {
+    if !inputs.mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some((window, cursor_position)) = pointer_cameras
+        .window()
+        .and_then(|window| window.cursor_position().map(|pos| (window, pos)))
+    else {
+        return;
+    };
+
+    let Some(camera_entity) = pointer_cameras.at_position(cursor_position) else {
+        return;
+    };
+
+    let settings = settings.get(camera_entity);
+    let now = time.elapsed_secs();
+
+    let is_double_click = last_click.is_some_and(|(time, position)| {
+        now - time <= settings.double_click_time
+            && position.distance(cursor_position) <= DOUBLE_CLICK_DISTANCE
+    });
+
+    if !is_double_click {
+        *last_click = Some((now, cursor_position));
+        return;
+    }
+    *last_click = None;
+
+    let Ok((controller, camera, camera_gt, ground)) = cam_q.get(camera_entity) else {
+        return;
+    };
+
+    let picked = settings
+        .anchor_on_picking_hits
+        .then(|| pointer_cameras.picking_hit(PointerId::Mouse, camera_entity))
+        .flatten();
+    let Some(zoom_target) = picked.or_else(|| {
+        screen_ground_point(
+            cursor_position,
+            camera,
+            camera_gt,
+            window,
+            controller,
+            ground,
+        )
+    }) else {
+        return;
+    };
+
+    let zoom_out = inputs
+        .pressed_binding(&settings.buttons.double_click_zoom_out)
+        .is_some();
+
+    if let Some(animation) = settings.double_click_zoom(zoom_target, zoom_out) {
+        commands.entity(camera_entity).insert(animation);
+    }
+}
+
 fn grab_pan(
     mut commands: Commands,
-    cam_q: Single<(Entity, &CameraController), With<Camera>>,
-    settings: Res<CameraControllerSettings>,
+    pointer_cameras: PointerCameras,
+    settings: ControllerSettings,
     inputs: Inputs,
-    mut first_ray_hit: Local<Option<Vec3>>,
-    primary_window_q: Single<Entity, With<PrimaryWindow>>,
-    mut camera_writer: MessageWriter<ControlMessage>,
+    mut first_ray_hit: Local<Option<(Entity, Vec3)>>,
+    mut camera_writer: ControlWriter,
     ray_map: Res<RayMap>,
 ) {
-    let (camera_entity, controller) = cam_q.into_inner();
-    let window_entity = primary_window_q.into_inner();
-    let drag_buttons = &settings.buttons.pan;
+    let Some(window_entity) = pointer_cameras.window_entity() else {
+        return;
+    };
 
-    if inputs.multi_pressed(drag_buttons) {
-        let Some(intersection_point) =
-            get_plane_intersection_point(controller, &ray_map.map, camera_entity)
-        else {
+    // Keep panning the same camera even if the cursor leaves its viewport. The second press of a
+    // double click zooms instead of grabbing.
+    let pressed_camera = first_ray_hit
+        .map(|(camera, _)| camera)
+        .or_else(|| {
+            pointer_cameras
+                .under_cursor()
+                .filter(|camera| !pointer_cameras.zoom_animating(*camera))
+        })
+        .filter(|camera| {
+            settings.get(*camera).buttons.drag_action(&inputs) == Some(DragAction::Pan)
+        });
+
+    if let Some(camera_entity) = pressed_camera {
+        let Some(controller) = pointer_cameras.controller(camera_entity) else {
+            return;
+        };
+
+        // Grab the ground under the cursor, then keep the grabbed point under the cursor by moving
+        // along the horizontal plane through it
+        let grabbed_height = first_ray_hit.map(|(_, first_hit)| first_hit.y);
+        let picked = (grabbed_height.is_none()
+            && settings.get(camera_entity).anchor_on_picking_hits)
+            .then(|| pointer_cameras.picking_hit(PointerId::Mouse, camera_entity))
+            .flatten();
+        let Some(intersection_point) = picked.or_else(|| {
+            get_plane_intersection_point(
+                controller,
+                pointer_cameras.ground(camera_entity),
+                grabbed_height,
+                &ray_map.map,
+                camera_entity,
+            )
+        }) else {
             //Grab pan pressed without first ray hit, return
             return;
         };
 
-        if let Some(first_hit) = *first_ray_hit {
+        if let Some((_, first_hit)) = *first_ray_hit {
             let first_hit_diff = first_hit - intersection_point;
 
-            camera_writer.write(ControlMessage::TranslateTarget(first_hit_diff));
+            camera_writer.write(ControlMessage::TranslateTarget(
+                camera_entity,
+                first_hit_diff,
+            ));
+            camera_writer.drag(camera_entity);
         } else {
             if let Ok(mut ecmd) = commands.get_entity(window_entity) {
                 ecmd.entry::<CursorIcon>()
@@ -142,7 +395,7 @@ fn grab_pan(
                     .or_insert(CursorIcon::System(SystemCursorIcon::Grabbing));
             }
 
-            *first_ray_hit = Some(intersection_point);
+            *first_ray_hit = Some((camera_entity, intersection_point));
         }
     } else if first_ray_hit.is_some() {
         *first_ray_hit = None;
@@ -156,6 +409,8 @@ fn grab_pan(
 
 fn get_plane_intersection_point(
     controller: &CameraController,
+    ground: Option<&Ground>,
+    grabbed_height: Option<f32>,
     ray_map: &HashMap<RayId, Ray3d>,
     camera_entity: Entity,
 ) -> Option<Vec3> {
@@ -169,12 +424,10 @@ fn get_plane_intersection_point(
         return None;
     };
 
-    let intersection_point = ray
-        .intersect_plane(
-            Vec3::Y * controller.grab_height,
-            InfinitePlane3d { normal: Dir3::Y },
-        )
-        .map(|distance: f32| ray.get_point(distance));
+    let intersection_point = match grabbed_height {
+        Some(height) => plane_point(*ray, height),
+        None => ground_point(*ray, controller, ground),
+    };
 
     if intersection_point.is_none() {
         warn!("Mouse Grab pan intersection did not intersect with Grab plane");
@@ -182,3 +435,112 @@ fn get_plane_intersection_point(
 
     intersection_point
 }
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_input::{keyboard::KeyCode, mouse::MouseScrollUnit};
+
+    use crate::{
+        CameraControllerSettings,
+        inputs::Binding,
+        look_angles::LookAngles,
+        test_utils::{MapCameraTestApp, WINDOW_HEIGHT, WINDOW_WIDTH},
+    };
+
+    const CENTER: Vec2 = Vec2::new(WINDOW_WIDTH as f32 / 2.0, WINDOW_HEIGHT as f32 / 2.0);
+
+    #[test]
+    fn test_double_click_zooms_without_grabbing() {
+        let mut harness = MapCameraTestApp::new();
+        let start = harness.look_transform();
+
+        harness.move_cursor(CENTER);
+        harness.press(MouseButton::Left);
+        harness.step();
+        harness.release(MouseButton::Left);
+        harness.step();
+        harness.press(MouseButton::Left);
+        harness.step();
+
+        // Dragging while the zoom plays doesn't pan
+        for frame in 1..=10 {
+            harness.move_cursor(CENTER + Vec2::Y * 10.0 * frame as f32);
+            harness.step();
+        }
+
+        let zoomed = harness.look_transform();
+        assert!(zoomed.radius() < start.radius() * 0.9);
+        assert!(zoomed.target.abs_diff_eq(start.target, 1e-3));
+    }
+
+    #[test]
+    fn test_double_click_zoom_out_takes_mouse_bindings() {
+        let mut harness = MapCameraTestApp::new();
+        harness.settings_mut().buttons.double_click_zoom_out =
+            vec![Binding::at_least([MouseButton::Back])];
+        let start = harness.look_transform();
+
+        harness.move_cursor(CENTER);
+        harness.press(MouseButton::Back);
+        for _ in 0..2 {
+            harness.press(MouseButton::Left);
+            harness.step();
+            harness.release(MouseButton::Left);
+            harness.step();
+        }
+        harness.step_frames(30);
+
+        assert!(harness.look_transform().radius() > start.radius() * 1.5);
+    }
+
+    #[test]
+    fn test_scroll_modifiers_tilt_and_rotate() {
+        let settings = CameraControllerSettings::default();
+
+        // Scrolling a line up tilts towards the horizon, or lowers the yaw
+        for (modifier, expected_pitch, expected_yaw) in [
+            (
+                KeyCode::ControlLeft,
+                -settings.scroll_tilt_sensitivity_modifier,
+                0.0,
+            ),
+            (
+                KeyCode::ShiftLeft,
+                0.0,
+                -settings.scroll_rotation_sensitivity_modifier,
+            ),
+        ] {
+            let mut harness = MapCameraTestApp::new();
+            let start = harness.look_transform();
+            let start_angles = LookAngles::from_vector(-start.look_direction().unwrap());
+
+            harness.move_cursor(CENTER);
+            harness.press(modifier);
+            harness.step();
+            harness.scroll(MouseScrollUnit::Line, Vec2::Y);
+            harness.step();
+
+            let scrolled = harness.look_transform();
+            let angles = LookAngles::from_vector(-scrolled.look_direction().unwrap());
+            let pitch = angles.get_pitch() - start_angles.get_pitch();
+            let yaw = angles.get_yaw() - start_angles.get_yaw();
+
+            assert!(
+                (pitch - expected_pitch).abs() < 1e-4,
+                "{modifier:?} pitch {pitch}"
+            );
+            assert!((yaw - expected_yaw).abs() < 1e-4, "{modifier:?} yaw {yaw}");
+            assert!((scrolled.radius() - start.radius()).abs() < 1e-4);
+            assert_eq!(scrolled.target, start.target);
+        }
+    }
+}
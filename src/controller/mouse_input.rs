@@ -1,6 +1,7 @@
 use std::ops::Deref;
 
-use crate::inputs::{InputButton, Inputs};
+use super::TrackpadMode;
+use crate::inputs::Inputs;
 use bevy_ecs::{prelude::*, system::SystemParam};
 use bevy_input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
 use bevy_math::Vec2;
@@ -20,27 +21,27 @@ impl<'w, 's> Deref for MouseKeyboardInputs<'w, 's> {
 }
 
 impl<'w, 's> MouseKeyboardInputs<'w, 's> {
-    pub fn mouse_drag(&mut self, buttons: &Vec<InputButton>) -> Option<Vec2> {
-        if !self.inputs.multi_pressed(buttons) {
-            return None;
-        }
-
-        let sum = self.ev_motion.read().map(|e| e.delta).sum::<Vec2>();
-
-        if sum.length_squared() > 0.0 {
-            Some(sum)
-        } else {
-            None
-        }
+    /// How far the mouse moved since the last read
+    pub fn mouse_motion(&mut self) -> Vec2 {
+        self.ev_motion.read().map(|e| e.delta).sum::<Vec2>()
     }
 
-    pub fn scroll_scalar(&mut self, pixels_per_line: f32, scroll_sensitivity: f32) -> Option<f32> {
-        if self.ev_scroll.len() == 0 {
-            return None;
-        }
+    /// The zoom of the scroll events since the last read, skipping the ones that pan in `trackpad` mode
+    pub fn scroll_scalar(
+        &mut self,
+        pixels_per_line: f32,
+        scroll_sensitivity: f32,
+        trackpad: TrackpadMode,
+    ) -> Option<f32> {
+        let mut zooms = self
+            .ev_scroll
+            .read()
+            .filter(|ev| !trackpad.scroll_pans(ev.unit))
+            .peekable();
+        zooms.peek()?;
 
         let mut scalar = 1.0;
-        for ev in self.ev_scroll.read() {
+        for ev in zooms {
             let scroll_amount = match ev.unit {
                 MouseScrollUnit::Line => ev.y,
                 MouseScrollUnit::Pixel => ev.y / pixels_per_line,
@@ -51,4 +52,29 @@ impl<'w, 's> MouseKeyboardInputs<'w, 's> {
 
         Some(scalar)
     }
+
+    /// How far the wheel scrolled since the last read, in lines, skipping the scrolls that pan in
+    /// `trackpad` mode
+    pub fn scroll_lines(&mut self, pixels_per_line: f32, trackpad: TrackpadMode) -> Option<Vec2> {
+        let mut scrolls = self
+            .ev_scroll
+            .read()
+            .filter(|ev| !trackpad.scroll_pans(ev.unit))
+            .peekable();
+        scrolls.peek()?;
+
+        Some(
+            scrolls
+                .map(|ev| match ev.unit {
+                    MouseScrollUnit::Line => Vec2::new(ev.x, ev.y),
+                    MouseScrollUnit::Pixel => Vec2::new(ev.x, ev.y) / pixels_per_line,
+                })
+                .sum(),
+        )
+    }
+
+    /// Marks all scroll events as read
+    pub fn clear_scroll(&mut self) {
+        self.ev_scroll.clear();
+    }
 }
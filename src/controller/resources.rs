@@ -1,23 +1,318 @@
-use crate::inputs::InputButton;
-use bevy_input::{keyboard::KeyCode, mouse::MouseButton};
+use crate::inputs::{Binding, InputButton, Inputs};
+use bevy_input::{
+    keyboard::KeyCode,
+    mouse::{MouseButton, MouseScrollUnit},
+};
 use bevy_reflect::Reflect;
 
+/// The bindings of the camera actions, an action can have any number of bindings.
+///
+/// A drag is done by the most specific pressed binding, the one with the most buttons, so holding
+/// Shift with the pan button rotates instead of panning.
 #[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct CameraControllerButtons {
-    pub pan: Vec<InputButton>,
-    /// Alternative pan key-binding
-    pub pan_alt: Option<Vec<InputButton>>,
-    pub rotate: Vec<InputButton>,
-    /// Alternative rotate key-binding
-    pub rotate_alt: Option<Vec<InputButton>>,
+    /// Drag to grab pan
+    pub pan: Vec<Binding>,
+    /// Drag to rotate
+    pub rotate: Vec<Binding>,
+    /// Drag up and down to only change the pitch
+    pub tilt: Vec<Binding>,
+    /// Drag up and down to zoom towards the target
+    pub zoom: Vec<Binding>,
+    /// Turns the camera to face north, a yaw of `0.0`, and to look as far down as the pitch limits
+    /// allow
+    pub reset: Vec<Binding>,
+    /// Hold to tilt, only change the pitch, with the scroll wheel instead of zooming
+    pub scroll_tilt: Vec<Binding>,
+    /// Hold to rotate, only change the yaw, with the scroll wheel instead of zooming
+    pub scroll_rotate: Vec<Binding>,
+    /// Hold to zoom instead of panning when scrolling with two fingers on a trackpad, see
+    /// [`TrackpadMode`]
+    pub trackpad_scroll_zoom: Vec<Binding>,
+    /// Holding any of these while double clicking zooms out instead of in
+    pub double_click_zoom_out: Vec<Binding>,
 }
 impl Default for CameraControllerButtons {
     fn default() -> Self {
         Self {
-            pan: vec![MouseButton::Left.into()],
-            rotate: vec![MouseButton::Left.into(), KeyCode::ShiftLeft.into()],
-            pan_alt: None,
-            rotate_alt: Some(vec![MouseButton::Right.into()]),
+            pan: vec![Binding::at_least([MouseButton::Left])],
+            rotate: vec![
+                Binding::at_least([
+                    InputButton::Mouse(MouseButton::Left),
+                    InputButton::Key(KeyCode::ShiftLeft),
+                ]),
+                Binding::at_least([MouseButton::Right]),
+            ],
+            tilt: vec![Binding::at_least([MouseButton::Middle])],
+            zoom: vec![Binding::at_least([
+                InputButton::Mouse(MouseButton::Right),
+                InputButton::Key(KeyCode::ShiftLeft),
+            ])],
+            reset: vec![Binding::at_least([KeyCode::Home])],
+            scroll_tilt: vec![
+                Binding::at_least([KeyCode::ControlLeft]),
+                Binding::at_least([KeyCode::ControlRight]),
+            ],
+            scroll_rotate: vec![
+                Binding::at_least([KeyCode::ShiftLeft]),
+                Binding::at_least([KeyCode::ShiftRight]),
+            ],
+            trackpad_scroll_zoom: vec![
+                Binding::at_least([KeyCode::ControlLeft]),
+                Binding::at_least([KeyCode::ControlRight]),
+            ],
+            double_click_zoom_out: vec![
+                Binding::at_least([KeyCode::AltLeft]),
+                Binding::at_least([KeyCode::AltRight]),
+            ],
         }
     }
 }
+
+/// How scrolling and the `bevy_input::gestures` of a trackpad control the camera.
+///
+/// Scrolling with two fingers pans when it is treated as a trackpad, a pinch zooms towards the cursor
+/// and twisting two fingers rotates. Pinch and rotation gestures are only sent on macOS and iOS, other
+/// platforms send a pinch as a scroll with Ctrl held. A scroll that pans zooms instead while a
+/// [`CameraControllerButtons::trackpad_scroll_zoom`] binding, Ctrl by default, is held, and doesn't
+/// use the [`CameraControllerButtons::scroll_tilt`] or [`CameraControllerButtons::scroll_rotate`]
+/// bindings.
+#[derive(Debug, Default, Clone, Copy, Reflect, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrackpadMode {
+    /// Scrolling zooms, like a mouse wheel, and gestures are ignored
+    #[default]
+    Off,
+    /// Scrolling pans
+    On,
+    /// Scrolling in pixels pans and scrolling in lines zooms. Trackpads scroll in pixels, but so do
+    /// some mice with smooth scrolling.
+    Detect,
+}
+
+impl TrackpadMode {
+    /// Returns true if a scroll in `unit` pans instead of zooming
+    pub fn scroll_pans(self, unit: MouseScrollUnit) -> bool {
+        match self {
+            TrackpadMode::Off => false,
+            TrackpadMode::On => true,
+            TrackpadMode::Detect => unit == MouseScrollUnit::Pixel,
+        }
+    }
+}
+
+/// What a drag with the mouse does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DragAction {
+    Pan,
+    Rotate,
+    Tilt,
+    Zoom,
+}
+
+/// What scrolling does, zooming if no modifier is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScrollAction {
+    Tilt,
+    Rotate,
+}
+
+impl CameraControllerButtons {
+    /// The drag action of the most specific pressed binding, the first action wins a tie
+    pub(crate) fn drag_action(&self, inputs: &Inputs) -> Option<DragAction> {
+        most_specific(
+            inputs,
+            [
+                (DragAction::Pan, &self.pan),
+                (DragAction::Rotate, &self.rotate),
+                (DragAction::Tilt, &self.tilt),
+                (DragAction::Zoom, &self.zoom),
+            ],
+        )
+    }
+
+    /// The scroll action of the most specific pressed binding, the first action wins a tie
+    pub(crate) fn scroll_action(&self, inputs: &Inputs) -> Option<ScrollAction> {
+        most_specific(
+            inputs,
+            [
+                (ScrollAction::Tilt, &self.scroll_tilt),
+                (ScrollAction::Rotate, &self.scroll_rotate),
+            ],
+        )
+    }
+}
+
+/// The action with the pressed binding that has the most buttons
+fn most_specific<A: Copy, const N: usize>(
+    inputs: &Inputs,
+    actions: [(A, &Vec<Binding>); N],
+) -> Option<A> {
+    let mut pressed: Option<(A, usize)> = None;
+
+    for (action, bindings) in actions {
+        if let Some(binding) = inputs.pressed_binding(bindings) {
+            if pressed.is_none_or(|(_, buttons)| binding.buttons.len() > buttons) {
+                pressed = Some((action, binding.buttons.len()));
+            }
+        }
+    }
+
+    pressed.map(|(action, _)| action)
+}
+
+/// Keys that move the camera while held, a movement happens if any of its keys are pressed
+#[derive(Clone, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct CameraControllerKeys {
+    pub pan_forward: Vec<KeyCode>,
+    pub pan_back: Vec<KeyCode>,
+    pub pan_left: Vec<KeyCode>,
+    pub pan_right: Vec<KeyCode>,
+    pub yaw_left: Vec<KeyCode>,
+    pub yaw_right: Vec<KeyCode>,
+    /// Moves the eye up, towards looking straight down on the target
+    pub pitch_up: Vec<KeyCode>,
+    pub pitch_down: Vec<KeyCode>,
+    pub zoom_in: Vec<KeyCode>,
+    pub zoom_out: Vec<KeyCode>,
+}
+impl Default for CameraControllerKeys {
+    fn default() -> Self {
+        Self {
+            pan_forward: vec![KeyCode::KeyW, KeyCode::ArrowUp],
+            pan_back: vec![KeyCode::KeyS, KeyCode::ArrowDown],
+            pan_left: vec![KeyCode::KeyA, KeyCode::ArrowLeft],
+            pan_right: vec![KeyCode::KeyD, KeyCode::ArrowRight],
+            yaw_left: vec![KeyCode::KeyQ],
+            yaw_right: vec![KeyCode::KeyE],
+            pitch_up: vec![KeyCode::KeyR],
+            pitch_down: vec![KeyCode::KeyF],
+            zoom_in: vec![KeyCode::Equal, KeyCode::NumpadAdd],
+            zoom_out: vec![KeyCode::Minus, KeyCode::NumpadSubtract],
+        }
+    }
+}
+
+/// How the camera keeps moving after a mouse or touch drag that pans, rotates or zooms is released
+#[derive(Clone, Copy, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct InertiaSettings {
+    /// Disabled by default
+    pub enabled: bool,
+    /// How fast the camera slows down, the speed decays with `exp(-friction * seconds)`
+    pub friction: f32,
+    /// How many seconds of movement before the release the glide speed is estimated from
+    pub sample_window: f32,
+}
+impl Default for InertiaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            friction: 4.0,
+            sample_window: 0.1,
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use bevy_input::ButtonInput;
+
+    fn drag_action(pressed: &[InputButton]) -> Option<DragAction> {
+        let mut world = World::new();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        let mut mouse = ButtonInput::<MouseButton>::default();
+        for button in pressed {
+            match button {
+                InputButton::Mouse(button) => mouse.press(*button),
+                InputButton::Key(key) => keys.press(*key),
+            }
+        }
+        world.insert_resource(keys);
+        world.insert_resource(mouse);
+
+        world
+            .run_system_once(|inputs: Inputs| {
+                CameraControllerButtons::default().drag_action(&inputs)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_most_specific_drag_action_wins() {
+        use InputButton::{Key, Mouse};
+
+        assert_eq!(
+            drag_action(&[Mouse(MouseButton::Left)]),
+            Some(DragAction::Pan)
+        );
+        assert_eq!(
+            drag_action(&[Mouse(MouseButton::Left), Key(KeyCode::ShiftLeft)]),
+            Some(DragAction::Rotate)
+        );
+        // An unrelated key doesn't stop a drag
+        assert_eq!(
+            drag_action(&[Mouse(MouseButton::Right), Key(KeyCode::KeyW)]),
+            Some(DragAction::Rotate)
+        );
+        assert_eq!(
+            drag_action(&[Mouse(MouseButton::Right), Key(KeyCode::ShiftLeft)]),
+            Some(DragAction::Zoom)
+        );
+        assert_eq!(
+            drag_action(&[Mouse(MouseButton::Middle)]),
+            Some(DragAction::Tilt)
+        );
+        assert_eq!(drag_action(&[Key(KeyCode::ShiftLeft)]), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bindings_load_from_ron() {
+        let buttons: CameraControllerButtons = ron::from_str(
+            "(
+                pan: [(buttons: [Mouse(Middle)])],
+                rotate: [(buttons: [Mouse(Left), Key(ControlLeft)], mode: Exact)],
+            )",
+        )
+        .unwrap();
+
+        assert_eq!(buttons.pan, vec![Binding::at_least([MouseButton::Middle])]);
+        assert_eq!(
+            buttons.rotate,
+            vec![Binding::exact([
+                InputButton::Mouse(MouseButton::Left),
+                InputButton::Key(KeyCode::ControlLeft),
+            ])]
+        );
+        assert_eq!(buttons.zoom, CameraControllerButtons::default().zoom);
+
+        let round_trip: CameraControllerButtons =
+            ron::from_str(&ron::to_string(&buttons).unwrap()).unwrap();
+        assert_eq!(round_trip.rotate, buttons.rotate);
+    }
+}
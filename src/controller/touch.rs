@@ -1,23 +1,24 @@
-use super::{CameraController, ray_from_screenspace};
+use super::{CameraController, PointerCameras, screen_ground_point};
 use bevy_app::{App, Plugin, PreUpdate};
 use bevy_camera::Camera;
 use bevy_ecs::prelude::*;
-use bevy_input::touch::Touches;
-use bevy_log::{error, warn};
-use bevy_math::{Dir3, Ray3d, Vec2, Vec3, primitives::InfinitePlane3d};
+use bevy_log::warn;
+use bevy_math::{Ray3d, Vec2, Vec3};
 use bevy_picking::{
     backend::ray::{RayId, RayMap},
     pointer::PointerId,
 };
 use bevy_platform::collections::HashMap;
 use bevy_transform::components::GlobalTransform;
-use bevy_window::{PrimaryWindow, Window};
 
-use crate::{CameraChange, LookTransform};
+use crate::CameraChange;
 
 use super::{
-    CameraControllerSettings, ControlMessage,
-    touch_inputs::{Pinch, TouchInputSettings, TouchInputs},
+    ControlMessage, ControlWriter, ControllerSettings, Ground,
+    ground::{ground_point, plane_point},
+    touch_inputs::{
+        OneTouchPan, Tap, TapState, TouchInputSettings, TouchInputs, TouchTaps, TwoTouchDrag,
+    },
 };
 
 pub(super) struct TouchInputPlugin;
@@ -26,112 +27,288 @@ impl Plugin for TouchInputPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<TouchInputSettings>();
         app.init_resource::<TouchInputSettings>();
+        app.init_resource::<TapState>();
         app.add_systems(
             PreUpdate,
-            (zoom_orbit_camera, grab_pan, rotate_orbit_camera).in_set(CameraChange::Before),
+            (
+                two_touch_gesture,
+                grab_pan.after(double_tap_zoom),
+                double_tap_zoom,
+            )
+                .in_set(CameraChange::Before),
         );
     }
 }
 
-/// Handles the zooming of the orbital camera
-fn zoom_orbit_camera(
+/// Twists, pinches and drags with two fingers in the same frame. Dragging moves the ground with the
+/// fingers, twisting turns it around the point between them and pinching zooms towards that point.
+/// Fingers side by side dragging up or down tilt instead of panning.
+fn two_touch_gesture(
     mut touches: TouchInputs,
-    cam_q: Single<(&Camera, &GlobalTransform, &LookTransform, &CameraController)>,
-    main_window: Single<&Window, With<PrimaryWindow>>,
-    settings: Res<CameraControllerSettings>,
-    mut camera_writer: MessageWriter<ControlMessage>,
+    cam_q: Query<(
+        &Camera,
+        &GlobalTransform,
+        &CameraController,
+        Option<&Ground>,
+    )>,
+    pointer_cameras: PointerCameras,
+    settings: ControllerSettings,
+    mut camera_writer: ControlWriter,
+    mut gesture_camera: Local<Option<(Entity, Option<Vec3>)>>,
 ) {
-    // Get the deltas of the two touches
-    let Some(Pinch {
-        distance_delta,
-        middle,
-    }) = touches.get_pinch()
-    else {
+    let Some(gesture) = touches.get_two_touch_gesture() else {
+        *gesture_camera = None;
         return;
     };
 
-    let (camera, camera_gt, camera_lt, controller) = cam_q.into_inner();
-    let window = main_window.into_inner();
+    let ground_point = |camera: Entity, screen_position: Vec2| {
+        let window = pointer_cameras.window()?;
+        let (camera_component, camera_gt, controller, ground) = cam_q.get(camera).ok()?;
+        screen_ground_point(
+            screen_position,
+            camera_component,
+            camera_gt,
+            window,
+            controller,
+            ground,
+        )
+    };
 
-    let scalar = 1.0 - distance_delta * settings.touch_zoom_sensitivity_modifier;
+    // Between what the fingers picked if both picked something, otherwise the ground between them
+    let middle_point = |camera: Entity| {
+        if settings.get(camera).anchor_on_picking_hits {
+            let hits = touches
+                .touches()
+                .iter()
+                .map(|touch| pointer_cameras.picking_hit(PointerId::Touch(touch.id()), camera))
+                .collect::<Option<Vec<_>>>();
+            if let Some([hit1, hit2]) = hits.as_deref() {
+                return Some((*hit1 + *hit2) / 2.0);
+            }
+        }
 
-    if scalar == 1.0 {
-        return;
-    }
+        ground_point(camera, gesture.middle)
+    };
 
-    let Ok(ray) = ray_from_screenspace(middle, camera, camera_gt, window) else {
-        camera_writer.write(ControlMessage::Zoom {
-            zoom_scalar: scalar,
-            zoom_target: camera_lt.target,
-        });
-        error!("Unable to create ray from screenspace");
-        return;
+    // The camera, and the pivot of the orbit, are found when the fingers go down and kept until they
+    // lift
+    let (camera, orbit_pivot) = match *gesture_camera {
+        Some(gesture_camera) => gesture_camera,
+        None => {
+            let Some(camera) = pointer_cameras.at_position(gesture.middle) else {
+                return;
+            };
+            let orbit_pivot = settings
+                .get(camera)
+                .orbit_around_pointer
+                .then(|| middle_point(camera))
+                .flatten();
+            *gesture_camera = Some((camera, orbit_pivot));
+            (camera, orbit_pivot)
+        }
     };
 
-    let Some(target_distance) = ray.intersect_plane(
-        Vec3::Y * controller.grab_height,
-        InfinitePlane3d { normal: Dir3::Y },
-    ) else {
-        warn!("Cursor click did not intersect with Grab plane");
+    let settings = settings.get(camera);
+    if !settings.touch_enabled {
         return;
+    }
+    camera_writer.drag(camera);
+
+    let orbit = |delta: Vec2, pivot: Option<Vec3>| match pivot {
+        Some(pivot) => ControlMessage::OrbitAround {
+            camera,
+            delta,
+            pivot,
+        },
+        None => ControlMessage::Orbit(camera, delta),
     };
 
-    let target = ray.get_point(target_distance);
+    // Move the ground that was under the middle of the fingers last frame to under it now, like
+    // grab panning does with one finger
+    let mut pan = Vec3::ZERO;
+    match gesture.drag {
+        Some(TwoTouchDrag::Pan(translation)) => {
+            match (
+                ground_point(camera, gesture.middle - translation),
+                ground_point(camera, gesture.middle),
+            ) {
+                (Some(grabbed), Some(under_middle)) => {
+                    pan = grabbed - under_middle;
+                    camera_writer.write(ControlMessage::TranslateTarget(camera, pan));
+                }
+                _ => warn!("Two finger pan did not intersect with Grab plane"),
+            }
+        }
+        Some(TwoTouchDrag::Tilt(delta)) => {
+            camera_writer.write(orbit(
+                Vec2::new(0.0, delta) * settings.touch_rotation_sensitivity_modifier,
+                orbit_pivot,
+            ));
+        }
+        None => {}
+    }
 
-    camera_writer.write(ControlMessage::Zoom {
-        zoom_scalar: scalar,
-        zoom_target: target,
-    });
+    if gesture.rotation.is_some() || gesture.scale.is_some() {
+        // The pan brings the grabbed point under the middle, pinch and twist around it
+        let middle_point = middle_point(camera).map(|point| point + pan);
+
+        // The ground turning clockwise on the screen is the camera turning the other way
+        if let Some(rotation) = gesture.rotation {
+            camera_writer.write(orbit(Vec2::new(-rotation, 0.0), middle_point));
+        }
+
+        match (gesture.scale, middle_point) {
+            (Some(scale), Some(zoom_target)) => {
+                camera_writer.write(ControlMessage::Zoom {
+                    camera,
+                    zoom_scalar: scale.recip().powf(settings.touch_pinch_zoom_exponent),
+                    zoom_target,
+                });
+            }
+            (Some(_), None) => warn!("Pinch did not intersect with Grab plane"),
+            _ => {}
+        }
+    }
 }
 
-/// Handles the rotation of the orbital camera, dont run in orthographic
-fn rotate_orbit_camera(
-    mut touches: TouchInputs,
-    settings: Res<CameraControllerSettings>,
-    mut camera_writer: MessageWriter<ControlMessage>,
+/// Zooms in towards a one finger double tap and out from a two finger tap. Dragging the second tap of a
+/// double tap down zooms in, and up zooms out.
+fn double_tap_zoom(
+    mut commands: Commands,
+    mut taps: TouchTaps,
+    mut camera_writer: ControlWriter,
+    cam_q: Query<(
+        &Camera,
+        &GlobalTransform,
+        &CameraController,
+        Option<&Ground>,
+    )>,
+    pointer_cameras: PointerCameras,
+    settings: ControllerSettings,
 ) {
-    let Some(rotation_move) = touches.get_two_touch_drag() else {
+    let tap = taps.read();
+    let position = match tap {
+        Some(Tap::Double(position) | Tap::TwoFinger(position))
+        | Some(Tap::DragZoom { position, .. }) => position,
+        None => return,
+    };
+
+    let Some(window) = pointer_cameras.window() else {
+        return;
+    };
+    let Some(camera_entity) = pointer_cameras.at_position(position) else {
+        return;
+    };
+    let Ok((camera, camera_gt, controller, ground)) = cam_q.get(camera_entity) else {
         return;
     };
-    camera_writer.write(ControlMessage::Orbit(
-        rotation_move * settings.touch_rotation_sensitivity_modifier,
-    ));
+
+    let settings = settings.get(camera_entity);
+    if !settings.touch_enabled {
+        return;
+    }
+
+    let Some(zoom_target) =
+        screen_ground_point(position, camera, camera_gt, window, controller, ground)
+    else {
+        return;
+    };
+
+    match tap {
+        Some(Tap::DragZoom { zoom_scalar, .. }) => {
+            camera_writer.write(ControlMessage::Zoom {
+                camera: camera_entity,
+                zoom_scalar,
+                zoom_target,
+            });
+            camera_writer.drag(camera_entity);
+        }
+        Some(tap) => {
+            let zoom_out = matches!(tap, Tap::TwoFinger(_));
+            if let Some(animation) = settings.double_click_zoom(zoom_target, zoom_out) {
+                commands.entity(camera_entity).insert(animation);
+            }
+        }
+        None => {}
+    }
+}
+
+/// State of an ongoing one finger grab pan
+#[derive(Clone, Copy)]
+struct TouchGrab {
+    camera: Entity,
+    first_ray_hit: Vec3,
+    first_screen_touch: Vec2,
+    over_threshold: bool,
 }
 
 fn grab_pan(
-    cam_q: Single<(Entity, &CameraController), With<Camera>>,
-    mut inputs: TouchInputs,
-    touches: Res<Touches>,
-    mut first_ray_hit: Local<Option<Vec3>>,
-    mut camera_writer: MessageWriter<ControlMessage>,
-    mut over_threshold: Local<bool>,
-    mut first_screen_touch: Local<Option<Vec2>>,
+    pointer_cameras: PointerCameras,
+    settings: ControllerSettings,
+    touches: OneTouchPan,
+    mut grab: Local<Option<TouchGrab>>,
+    mut camera_writer: ControlWriter,
     ray_map: Res<RayMap>,
 ) {
-    let (camera_entity, controller) = cam_q.into_inner();
+    // Keep panning the camera the grab started in, otherwise use the camera under the only touch. A
+    // double tap held down zooms instead.
+    let camera_entity = grab.map(|grab| grab.camera).or_else(|| {
+        let mut pressed = touches.touches().iter();
+        match (pressed.next(), pressed.next()) {
+            (Some(touch), None) => pointer_cameras.at_position(touch.position()),
+            _ => None,
+        }
+    });
+    let camera_entity = camera_entity
+        .filter(|camera| settings.get(*camera).touch_enabled && !touches.holding_double_tap());
 
-    let intersection = get_plane_intersection_point(controller, &ray_map.map, camera_entity).map(
-        |(pointer_id, point)| {
-            (
-                pointer_id
-                    .get_touch_id()
-                    .and_then(|id| touches.get_pressed(id).map(|touch| touch.position())),
-                point,
-            )
-        },
-    );
+    let Some((camera_entity, controller)) = camera_entity.and_then(|camera| {
+        pointer_cameras
+            .controller(camera)
+            .map(|controller| (camera, controller))
+    }) else {
+        *grab = None;
+        return;
+    };
+
+    // Grab the ground under the touch, then keep the grabbed point under the touch by moving along
+    // the horizontal plane through it
+    let intersection = get_plane_intersection_point(
+        controller,
+        pointer_cameras.ground(camera_entity),
+        grab.map(|grab| grab.first_ray_hit.y),
+        &ray_map.map,
+        camera_entity,
+    )
+    .map(|(pointer_id, point)| {
+        let picked = (grab.is_none() && settings.get(camera_entity).anchor_on_picking_hits)
+            .then(|| pointer_cameras.picking_hit(pointer_id, camera_entity))
+            .flatten();
+
+        (
+            pointer_id.get_touch_id().and_then(|id| {
+                touches
+                    .touches()
+                    .get_pressed(id)
+                    .map(|touch| touch.position())
+            }),
+            picked.unwrap_or(point),
+        )
+    });
 
     if let Err(TouchIntersectionPointError::NoIntersection) = intersection {
         warn!("Touch Grab pan intersection did not intersect with Grab plane");
     }
 
-    if first_ray_hit.is_none() {
+    if grab.is_none() {
         match intersection {
             Ok((Some(touch_pos), intersection_point)) => {
-                *first_ray_hit = Some(intersection_point);
-                *over_threshold = false;
-
-                *first_screen_touch = Some(touch_pos);
+                *grab = Some(TouchGrab {
+                    camera: camera_entity,
+                    first_ray_hit: intersection_point,
+                    first_screen_touch: touch_pos,
+                    over_threshold: false,
+                });
             }
             Ok((None, _)) => {
                 warn!("Tried to start Touch grab pan but no touch position was found");
@@ -142,26 +319,31 @@ fn grab_pan(
 
     if (intersection == Err(TouchIntersectionPointError::NoTouchRay)
         || intersection == Err(TouchIntersectionPointError::MultipleTouchRays))
-        && first_ray_hit.is_some()
+        && grab.is_some()
     {
-        *first_ray_hit = None;
-        inputs.clear_last_touches();
+        *grab = None;
     }
 
-    if let (Ok((Some(touch_pos), point)), Some(first_hit), Some(screen_touch)) =
-        (intersection, *first_ray_hit, *first_screen_touch)
-    {
-        let first_hit_diff = first_hit - point;
+    if let (Ok((Some(touch_pos), point)), Some(grab)) = (intersection, grab.as_mut()) {
+        let first_hit_diff = grab.first_ray_hit - point;
 
-        if touch_pos.distance(screen_touch) > 3.0 || *over_threshold {
-            *over_threshold = true;
-            camera_writer.write(ControlMessage::TranslateTarget(first_hit_diff));
+        if touch_pos.distance(grab.first_screen_touch) > touches.pan_threshold()
+            || grab.over_threshold
+        {
+            grab.over_threshold = true;
+            camera_writer.write(ControlMessage::TranslateTarget(
+                camera_entity,
+                first_hit_diff,
+            ));
+            camera_writer.drag(camera_entity);
         }
     }
 }
 
 fn get_plane_intersection_point(
     controller: &CameraController,
+    ground: Option<&Ground>,
+    grabbed_height: Option<f32>,
     ray_map: &HashMap<RayId, Ray3d>,
     camera_entity: Entity,
 ) -> Result<(PointerId, Vec3), TouchIntersectionPointError> {
@@ -180,12 +362,14 @@ fn get_plane_intersection_point(
         .pop()
         .ok_or(TouchIntersectionPointError::NoTouchRay)?;
 
-    ray.intersect_plane(
-        Vec3::Y * controller.grab_height,
-        InfinitePlane3d { normal: Dir3::Y },
-    )
-    .map(|distance: f32| (pointer_id, ray.get_point(distance)))
-    .ok_or(TouchIntersectionPointError::NoIntersection)
+    let intersection_point = match grabbed_height {
+        Some(height) => plane_point(*ray, height),
+        None => ground_point(*ray, controller, ground),
+    };
+
+    intersection_point
+        .map(|point| (pointer_id, point))
+        .ok_or(TouchIntersectionPointError::NoIntersection)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -194,3 +378,97 @@ enum TouchIntersectionPointError {
     MultipleTouchRays,
     NoTouchRay,
 }
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_input::touch::TouchPhase;
+    use bevy_picking::{backend::HitData, hover::HoverMap};
+
+    use crate::test_utils::{MapCameraTestApp, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+    const CENTER: Vec2 = Vec2::new(WINDOW_WIDTH as f32 / 2.0, WINDOW_HEIGHT as f32 / 2.0);
+
+    fn screen_position(harness: &MapCameraTestApp, point: Vec3) -> Vec2 {
+        let (camera, transform) = harness
+            .app
+            .world()
+            .entity(harness.camera)
+            .get_components::<(&Camera, &GlobalTransform)>()
+            .unwrap();
+        camera.world_to_viewport(transform, point).unwrap()
+    }
+
+    #[test]
+    fn test_pinch_zooms_towards_picked_point() {
+        let mut harness = MapCameraTestApp::new();
+        harness.settings_mut().anchor_on_picking_hits = true;
+
+        // Each finger picks a side of a rooftop that isn't on the ground under the fingers
+        let camera = harness.camera;
+        let mut hover_map = HoverMap::default();
+        for (id, hit) in [
+            (0, Vec3::new(-1.0, 2.0, -4.0)),
+            (1, Vec3::new(1.0, 2.0, -4.0)),
+        ] {
+            let hit = HitData::new(camera, 10.0, Some(hit), None);
+            let entity = harness.app.world_mut().spawn_empty().id();
+            hover_map.insert(PointerId::Touch(id), [(entity, hit)].into_iter().collect());
+        }
+        harness.app.insert_resource(hover_map);
+
+        let rooftop = Vec3::new(0.0, 2.0, -4.0);
+        let start = screen_position(&harness, rooftop);
+        let start_radius = harness.look_transform().radius();
+
+        harness.touch(TouchPhase::Started, 0, CENTER - Vec2::X * 100.0);
+        harness.touch(TouchPhase::Started, 1, CENTER + Vec2::X * 100.0);
+        harness.step();
+        harness.touch(TouchPhase::Moved, 0, CENTER - Vec2::X * 150.0);
+        harness.touch(TouchPhase::Moved, 1, CENTER + Vec2::X * 150.0);
+        harness.step();
+
+        // Spreading the fingers to 1.5 times as far apart zooms in as much
+        assert!((harness.look_transform().radius() - start_radius / 1.5).abs() < 1e-3);
+        assert!(screen_position(&harness, rooftop).distance(start) < 0.5);
+    }
+
+    #[test]
+    fn test_two_finger_pan_and_pinch_keep_ground_under_fingers() {
+        let mut harness = MapCameraTestApp::new();
+
+        let grabbed = harness
+            .ray(CENTER)
+            .and_then(|ray| plane_point(ray, 0.0))
+            .unwrap();
+        let start_radius = harness.look_transform().radius();
+
+        harness.touch(TouchPhase::Started, 0, CENTER - Vec2::X * 100.0);
+        harness.touch(TouchPhase::Started, 1, CENTER + Vec2::X * 100.0);
+        harness.step();
+
+        // Drag far enough to pan, then keep dragging while spreading the fingers
+        let mut middle = CENTER;
+        for (offset, spread) in [
+            (Vec2::new(40.0, 20.0), 100.0),
+            (Vec2::new(20.0, 10.0), 150.0),
+        ] {
+            middle += offset;
+            harness.touch(TouchPhase::Moved, 0, middle - Vec2::X * spread);
+            harness.touch(TouchPhase::Moved, 1, middle + Vec2::X * spread);
+            harness.step();
+
+            assert!(screen_position(&harness, grabbed).distance(middle) < 0.5);
+        }
+
+        assert!(harness.look_transform().radius() < start_radius);
+    }
+}
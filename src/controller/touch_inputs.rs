@@ -1,127 +1,599 @@
 use bevy_ecs::prelude::*;
 use bevy_ecs::{reflect::ReflectResource, system::SystemParam};
-use bevy_input::touch::{Touch, Touches};
+use bevy_input::touch::Touches;
 use bevy_math::Vec2;
 use bevy_reflect::Reflect;
+use bevy_time::{Real, Time};
 
+/// The smallest [`TouchInputSettings::pixel_scale`] that is used, anything lower would divide by zero.
+const MIN_PIXEL_SCALE: f32 = 0.01;
+
+/// How touches are recognised as gestures, shared by all cameras.
+///
+/// Distances are in logical pixels, the same as the touch positions. Bevy divides the physical touch
+/// positions by `Window::scale_factor`, so a threshold covers the same physical size on high DPI
+/// screens without reading the scale factor here. [`Self::pixel_scale`] is an extra multiplier on top
+/// of that, for screens where a logical pixel is smaller or larger than usual.
 #[derive(Resource, Clone, Copy, Reflect)]
 #[reflect(Resource)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct TouchInputSettings {
-    allowed_pinch_delta_diff: f32,
-    pinch_threshold: f32,
-    drag_threshold: f32,
+    /// How much the distance between two touches needs to change, in pixels, before they zoom
+    pub pinch_threshold: f32,
+    /// How far two touches need to move together, in pixels, before they orbit or tilt
+    pub drag_threshold: f32,
+    /// How far two fingers need to twist, in radians, before they start rotating the camera
+    pub twist_threshold: f32,
+    /// How far one touch needs to move, in pixels, before it starts panning
+    pub pan_threshold: f32,
+    /// Longest time a touch can be down and still be a tap, in seconds
+    pub tap_time: f32,
+    /// Longest time between the taps of a double tap, in seconds
+    pub double_tap_time: f32,
+    /// Furthest a touch can move and still be a tap, in pixels
+    pub tap_max_distance: f32,
+    /// Largest angle from vertical, in radians, of a two finger drag that tilts instead of rotating
+    pub tilt_max_angle: f32,
+    /// How much a double tap held down and dragged zooms, per pixel dragged
+    pub double_tap_drag_zoom_sensitivity: f32,
+    /// Multiplies every distance in pixels, `2.0` doubles the thresholds and halves the zoom of a
+    /// double tap drag. This is not the window's scale factor, which the logical pixels already
+    /// include. Values below `0.01` are treated as `0.01`
+    pub pixel_scale: f32,
 }
 
 impl Default for TouchInputSettings {
     fn default() -> Self {
         Self {
-            pinch_threshold: 1.0,
-            allowed_pinch_delta_diff: 1.0,
+            pinch_threshold: 5.0,
             drag_threshold: 5.0,
+            twist_threshold: 0.2,
+            pan_threshold: 3.0,
+            tap_time: 0.25,
+            double_tap_time: 0.3,
+            tap_max_distance: 10.0,
+            tilt_max_angle: 0.35,
+            double_tap_drag_zoom_sensitivity: 0.01,
+            pixel_scale: 1.0,
         }
     }
 }
 
-pub(super) struct Pinch {
-    /// The delta of the distances between the current touches compared to last frame.
-    pub distance_delta: f32,
+impl TouchInputSettings {
+    pub fn with_pinch_threshold(mut self, pinch_threshold: f32) -> Self {
+        self.pinch_threshold = pinch_threshold;
+        self
+    }
+
+    pub fn with_drag_threshold(mut self, drag_threshold: f32) -> Self {
+        self.drag_threshold = drag_threshold;
+        self
+    }
+
+    pub fn with_twist_threshold(mut self, twist_threshold: f32) -> Self {
+        self.twist_threshold = twist_threshold;
+        self
+    }
+
+    pub fn with_pan_threshold(mut self, pan_threshold: f32) -> Self {
+        self.pan_threshold = pan_threshold;
+        self
+    }
+
+    /// Sets the longest time a tap can last and the longest time between the taps of a double tap, in
+    /// seconds
+    pub fn with_tap_times(mut self, tap_time: f32, double_tap_time: f32) -> Self {
+        self.tap_time = tap_time;
+        self.double_tap_time = double_tap_time;
+        self
+    }
+
+    pub fn with_tap_max_distance(mut self, tap_max_distance: f32) -> Self {
+        self.tap_max_distance = tap_max_distance;
+        self
+    }
+
+    pub fn with_tilt_max_angle(mut self, tilt_max_angle: f32) -> Self {
+        self.tilt_max_angle = tilt_max_angle;
+        self
+    }
+
+    pub fn with_double_tap_drag_zoom_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.double_tap_drag_zoom_sensitivity = sensitivity;
+        self
+    }
+
+    pub fn with_pixel_scale(mut self, pixel_scale: f32) -> Self {
+        self.pixel_scale = pixel_scale.max(MIN_PIXEL_SCALE);
+        self
+    }
+
+    /// [`Self::pixel_scale`], kept positive even when the field was set to zero
+    fn pixel_scale(&self) -> f32 {
+        self.pixel_scale.max(MIN_PIXEL_SCALE)
+    }
+
+    /// A distance in pixels scaled by [`Self::pixel_scale`]
+    pub(super) fn pixels(&self, distance: f32) -> f32 {
+        distance * self.pixel_scale()
+    }
+}
+
+pub(super) enum TwoTouchDrag {
+    /// Both fingers moving together, by how far the middle of the touches moved
+    Pan(Vec2),
+    /// Both fingers, side by side, moving up or down
+    Tilt(f32),
+}
+
+/// The movement of two touches since last frame, split into a drag, a pinch and a twist. Each part is
+/// `None` until it has moved past its threshold since the touches went down.
+pub(super) struct TwoTouchGesture {
     /// The screen location between the current touches
     pub middle: Vec2,
+    /// How far the middle of the touches moved
+    pub drag: Option<TwoTouchDrag>,
+    /// The distance between the touches divided by the distance last frame
+    pub scale: Option<f32>,
+    /// How far the line between the touches turned, in radians, clockwise on the screen
+    pub rotation: Option<f32>,
 }
 
-/// Only one of [`Self::get_pinch`], [`Self::get_one_touch_drag`] and [`Self::get_two_touch_drag`]
-/// can be used in the same system, otherwise they will overwrite each-others last touches.
+/// An ongoing two finger gesture, from the fingers going down until one of them lifts
+#[derive(Default)]
+struct TwoTouchState {
+    /// The id and position of the touches last frame
+    last_touches: Option<[(u64, Vec2); 2]>,
+    /// How far the middle moved since the touches went down
+    moved: Vec2,
+    /// How much the distance between the touches changed since they went down
+    stretched: f32,
+    /// How far the touches twisted since they went down
+    twisted: f32,
+    /// Whether the drag tilts, decided when it passes the drag threshold
+    tilting: Option<bool>,
+    zooming: bool,
+    twisting: bool,
+}
+
+/// Recognises the gesture of two touches, the only touch input that needs the touches of last frame
 #[derive(SystemParam)]
 pub(super) struct TouchInputs<'w, 's> {
     touch_settings: Res<'w, TouchInputSettings>,
     touches: Res<'w, Touches>,
-    last_touch_1: Local<'s, Option<Vec2>>,
-    last_touch_2: Local<'s, Option<Vec2>>,
+    two_touches: Local<'s, TwoTouchState>,
 }
 
 impl<'w, 's> TouchInputs<'w, 's> {
-    fn get_two_touches(&mut self) -> Option<[(Touch, Vec2); 2]> {
+    /// The movement of exactly two touches since last frame
+    pub fn get_two_touch_gesture(&mut self) -> Option<TwoTouchGesture> {
         if self.touches.any_just_released() {
-            *self.last_touch_1 = None;
-            *self.last_touch_2 = None;
+            self.clear_last_touches();
         }
 
-        let touches: Vec<&Touch> = self.touches.iter().collect();
-
-        // If less than or more than two touches, return None
-        if touches.len() != 2 {
+        // Keep the touches in the same order every frame
+        let mut touches = self.touches.iter();
+        let (Some(touch1), Some(touch2), None) = (touches.next(), touches.next(), touches.next())
+        else {
             return None;
+        };
+        let (touch1, touch2) = if touch1.id() < touch2.id() {
+            (touch1, touch2)
+        } else {
+            (touch2, touch1)
+        };
+        let current = [
+            (touch1.id(), touch1.position()),
+            (touch2.id(), touch2.position()),
+        ];
+
+        let settings = &*self.touch_settings;
+        let state = &mut *self.two_touches;
+        let last = match state.last_touches {
+            Some(last) if last[0].0 == current[0].0 && last[1].0 == current[1].0 => last,
+            _ => current,
+        };
+        state.last_touches = Some(current);
+
+        let ((_, last1), (_, last2)) = (last[0], last[1]);
+        let ((_, position1), (_, position2)) = (current[0], current[1]);
+
+        let middle = (position1 + position2) / 2.0;
+        let translation = middle - (last1 + last2) / 2.0;
+        let last_distance = last1.distance(last2);
+        let distance = position1.distance(position2);
+        let rotation = (last2 - last1).angle_to(position2 - position1);
+
+        state.moved += translation;
+        state.stretched += distance - last_distance;
+        state.twisted += rotation;
+
+        // A finger moving around a still one also moves the middle, only drag when the fingers move
+        // together more than they pinch or twist
+        if state.tilting.is_none()
+            && state.moved.length() >= settings.pixels(settings.drag_threshold)
+            && state.moved.length()
+                > state
+                    .stretched
+                    .abs()
+                    .max(state.twisted.abs() * distance / 2.0)
+        {
+            // A drag that starts close to vertical with the fingers side by side tilts
+            let between = (position2 - position1).abs();
+            let angle = state.moved.x.abs().atan2(state.moved.y.abs());
+            state.tilting = Some(between.x > between.y && angle <= settings.tilt_max_angle);
         }
+        state.zooming |= state.stretched.abs() >= settings.pixels(settings.pinch_threshold);
+        state.twisting |= state.twisted.abs() >= settings.twist_threshold;
 
-        let touch1 = touches[0].clone();
-        let touch2 = touches[1].clone();
+        Some(TwoTouchGesture {
+            middle,
+            drag: state.tilting.map(|tilting| {
+                if tilting {
+                    TwoTouchDrag::Tilt(translation.y)
+                } else {
+                    TwoTouchDrag::Pan(translation)
+                }
+            }),
+            scale: (state.zooming && last_distance > 0.0).then(|| distance / last_distance),
+            rotation: state.twisting.then_some(rotation),
+        })
+    }
 
-        let last1 = self.last_touch_1.unwrap_or(touch1.position());
-        let last2 = self.last_touch_2.unwrap_or(touch2.position());
+    pub fn touches(&self) -> &Touches {
+        &self.touches
+    }
 
-        *self.last_touch_1 = Some(touch1.position());
-        *self.last_touch_2 = Some(touch2.position());
+    fn clear_last_touches(&mut self) {
+        *self.two_touches = TwoTouchState::default();
+    }
+}
+
+/// The touches of a one finger pan, which compares each touch with where it started instead of with
+/// last frame
+#[derive(SystemParam)]
+pub(super) struct OneTouchPan<'w> {
+    touch_settings: Res<'w, TouchInputSettings>,
+    touches: Res<'w, Touches>,
+    tap_state: Res<'w, TapState>,
+}
 
-        Some([(touch1, last1), (touch2, last2)])
+impl<'w> OneTouchPan<'w> {
+    pub fn touches(&self) -> &Touches {
+        &self.touches
     }
 
-    pub fn get_pinch(&mut self) -> Option<Pinch> {
-        let [(touch1, last_a), (touch2, last_b)] = self.get_two_touches()?;
+    /// How far a touch needs to move from where it started before it pans, in pixels
+    pub fn pan_threshold(&self) -> f32 {
+        self.touch_settings
+            .pixels(self.touch_settings.pan_threshold)
+    }
 
-        let d1 = touch1.position() - last_a;
-        let d2 = touch2.position() - last_b;
+    /// Returns true while a double tap is held down, which zooms instead of panning
+    pub fn holding_double_tap(&self) -> bool {
+        self.tap_state.holding_double_tap()
+    }
+}
 
-        if d1 == Vec2::ZERO || d2 == Vec2::ZERO {
-            return None;
+pub(super) enum Tap {
+    /// Two one finger taps at the same place
+    Double(Vec2),
+    /// Two fingers tapping at the same time, at the middle of the touches
+    TwoFinger(Vec2),
+    /// The second tap of a double tap held down and dragged, zooms by `zoom_scalar` around where it
+    /// was tapped
+    DragZoom { position: Vec2, zoom_scalar: f32 },
+}
+
+/// The touches of an ongoing tap, from the first finger down until all fingers are lifted
+struct TapGesture {
+    start: f32,
+    start_positions: Vec<Vec2>,
+    moved: bool,
+}
+
+/// The second touch of a double tap while it is held down
+struct DoubleTapHold {
+    id: u64,
+    last_position: Vec2,
+}
+
+/// The taps in progress, a resource so that one finger panning can stay still while a double tap is
+/// held down
+#[derive(Resource, Default)]
+pub(super) struct TapState {
+    gesture: Option<TapGesture>,
+    /// When the last one finger tap started, and where
+    last_tap: Option<(f32, Vec2)>,
+    double_tap_hold: Option<DoubleTapHold>,
+}
+
+impl TapState {
+    /// Returns true while the second touch of a double tap is held down
+    pub fn holding_double_tap(&self) -> bool {
+        self.double_tap_hold.is_some()
+    }
+}
+
+#[derive(SystemParam)]
+pub(super) struct TouchTaps<'w> {
+    touch_settings: Res<'w, TouchInputSettings>,
+    touches: Res<'w, Touches>,
+    time: Res<'w, Time<Real>>,
+    state: ResMut<'w, TapState>,
+}
+
+impl<'w> TouchTaps<'w> {
+    /// Returns the tap that was finished this frame, or the zoom of a held double tap, if any
+    pub fn read(&mut self) -> Option<Tap> {
+        let now = self.time.elapsed_secs();
+        let settings = *self.touch_settings;
+        let state = &mut *self.state;
+
+        for touch in self.touches.iter_just_pressed() {
+            let position = touch.start_position();
+
+            // A finger pressed soon after a one finger tap, at the same place, can be held down and
+            // dragged to zoom
+            state.double_tap_hold = match (&state.gesture, state.last_tap) {
+                (None, Some((last_start, last_position)))
+                    if now - last_start <= settings.double_tap_time
+                        && last_position.distance(position)
+                            <= settings.pixels(settings.tap_max_distance) =>
+                {
+                    Some(DoubleTapHold {
+                        id: touch.id(),
+                        last_position: position,
+                    })
+                }
+                _ => None,
+            };
+
+            state
+                .gesture
+                .get_or_insert_with(|| TapGesture {
+                    start: now,
+                    start_positions: Vec::new(),
+                    moved: false,
+                })
+                .start_positions
+                .push(position);
         }
 
-        let distance = touch1.position().distance(touch2.position());
+        let gesture = state.gesture.as_mut()?;
+        gesture.moved |= self.touches.any_just_canceled()
+            || self
+                .touches
+                .iter()
+                .chain(self.touches.iter_just_released())
+                .any(|touch| {
+                    touch.distance().length() > settings.pixels(settings.tap_max_distance)
+                });
+
+        let held = state.double_tap_hold.as_mut().and_then(|hold| {
+            let touch = self.touches.get_pressed(hold.id)?;
+            (gesture.start_positions.len() == 1).then_some((hold, touch))
+        });
+        match held {
+            Some((hold, touch)) if gesture.moved => {
+                let delta = touch.position().y - hold.last_position.y;
+                hold.last_position = touch.position();
+                state.last_tap = None;
 
-        if d1.dot(d2) > self.touch_settings.allowed_pinch_delta_diff {
+                // Dragging down zooms in
+                return (delta != 0.0).then(|| Tap::DragZoom {
+                    position: touch.start_position(),
+                    zoom_scalar: (-delta / settings.pixel_scale()
+                        * settings.double_tap_drag_zoom_sensitivity)
+                        .exp(),
+                });
+            }
+            Some(_) => {}
+            None => state.double_tap_hold = None,
+        }
+
+        // Wait for all fingers to be lifted
+        if self.touches.iter().next().is_some() {
             return None;
         }
 
-        let previous_distance = touch1
-            .previous_position()
-            .distance(touch2.previous_position());
+        let gesture = state.gesture.take()?;
+        if gesture.moved || now - gesture.start > settings.tap_time {
+            return None;
+        }
 
-        let distance_delta = distance - previous_distance;
+        let position =
+            gesture.start_positions.iter().sum::<Vec2>() / gesture.start_positions.len() as f32;
 
-        //If the distance delta is below the threshold dont zoom
-        if distance_delta.abs() < self.touch_settings.pinch_threshold {
-            return None;
+        match gesture.start_positions.len() {
+            1 => match state.last_tap.take() {
+                Some((last_start, last_position))
+                    if gesture.start - last_start <= settings.double_tap_time
+                        && last_position.distance(position)
+                            <= settings.pixels(settings.tap_max_distance) =>
+                {
+                    Some(Tap::Double(position))
+                }
+                _ => {
+                    state.last_tap = Some((gesture.start, position));
+                    None
+                }
+            },
+            2 => Some(Tap::TwoFinger(position)),
+            _ => None,
         }
+    }
+}
 
-        let middle = (touch1.previous_position() + touch2.previous_position()) / 2.0;
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
 
-        Some(Pinch {
-            distance_delta,
-            middle,
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_app::prelude::*;
+    use bevy_input::touch::{TouchInput, TouchPhase, touch_screen_input_system};
+
+    #[derive(Resource, Default)]
+    struct ReadTaps(Vec<Tap>);
+
+    fn touch(app: &mut App, phase: TouchPhase, id: u64, position: Vec2) {
+        app.world_mut().write_message(TouchInput {
+            phase,
+            position,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id,
+        });
+        app.update();
     }
 
-    /// Can't be used in the same system as [`Self::get_pinch`]
-    pub fn get_two_touch_drag(&mut self) -> Option<Vec2> {
-        let [(touch1, last_a), (touch2, last_b)] = self.get_two_touches()?;
+    fn taps_app(settings: TouchInputSettings) -> App {
+        let mut app = App::new();
+        app.add_message::<TouchInput>();
+        app.init_resource::<Touches>();
+        app.init_resource::<Time<Real>>();
+        app.insert_resource(settings);
+        app.init_resource::<TapState>();
+        app.init_resource::<ReadTaps>();
+        app.add_systems(PreUpdate, touch_screen_input_system);
+        app.add_systems(Update, |mut taps: TouchTaps, mut read: ResMut<ReadTaps>| {
+            read.0.extend(taps.read());
+        });
+        app
+    }
 
-        let d1 = touch1.position() - last_a;
-        let d2 = touch2.position() - last_b;
+    /// Double taps at `position` and drags the second tap down by `distance`
+    fn double_tap_drag(app: &mut App, position: Vec2, distance: f32) {
+        touch(app, TouchPhase::Started, 0, position);
+        touch(app, TouchPhase::Ended, 0, position);
+        touch(app, TouchPhase::Started, 1, position);
+        assert!(app.world().resource::<TapState>().holding_double_tap());
 
-        let dist = d1.distance(d2);
-        let avg = (d1 + d2) / 2.0;
+        touch(app, TouchPhase::Moved, 1, position + Vec2::Y * distance);
+        touch(app, TouchPhase::Ended, 1, position + Vec2::Y * distance);
+    }
 
-        // If the touches arent moving together or the moved distance is too small, return Vec2::ZERO
-        if dist > self.touch_settings.drag_threshold
-            || avg.length_squared() < self.touch_settings.drag_threshold
-        {
-            return None;
-        }
+    #[test]
+    fn test_held_double_tap_drags_to_zoom() {
+        let mut app = taps_app(TouchInputSettings::default());
+
+        // Dragging down zooms in around the tap
+        let position = Vec2::new(100.0, 100.0);
+        double_tap_drag(&mut app, position, 50.0);
 
-        Some(avg)
+        let taps = &app.world().resource::<ReadTaps>().0;
+        assert_eq!(taps.len(), 1);
+        let Tap::DragZoom {
+            position: zoomed_at,
+            zoom_scalar,
+        } = taps[0]
+        else {
+            panic!("expected a drag zoom");
+        };
+        assert_eq!(zoomed_at, position);
+        assert!(zoom_scalar < 1.0);
+        assert!(!app.world().resource::<TapState>().holding_double_tap());
     }
 
-    pub fn clear_last_touches(&mut self) {
-        *self.last_touch_1 = None;
-        *self.last_touch_2 = None;
+    #[test]
+    fn test_zero_pixel_scale_still_zooms() {
+        let settings = TouchInputSettings {
+            pixel_scale: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(settings.with_pixel_scale(0.0).pixel_scale, MIN_PIXEL_SCALE);
+
+        let mut app = taps_app(settings);
+        double_tap_drag(&mut app, Vec2::new(100.0, 100.0), 1.0);
+
+        let taps = &app.world().resource::<ReadTaps>().0;
+        let Some(Tap::DragZoom { zoom_scalar, .. }) = taps.first() else {
+            panic!("expected a drag zoom");
+        };
+        assert!(zoom_scalar.is_finite());
+        assert!(*zoom_scalar > 0.0 && *zoom_scalar < 1.0);
+    }
+
+    #[test]
+    fn test_twist_and_pinch_in_the_same_gesture() {
+        #[derive(Resource, Default)]
+        struct Gestures(Vec<(bool, Option<f32>, Option<f32>)>);
+
+        let mut app = App::new();
+        app.add_message::<TouchInput>();
+        app.init_resource::<Touches>();
+        app.init_resource::<TouchInputSettings>();
+        app.init_resource::<Gestures>();
+        app.add_systems(PreUpdate, touch_screen_input_system);
+        app.add_systems(
+            Update,
+            |mut touches: TouchInputs, mut gestures: ResMut<Gestures>| {
+                if let Some(gesture) = touches.get_two_touch_gesture() {
+                    gestures
+                        .0
+                        .push((gesture.drag.is_some(), gesture.scale, gesture.rotation));
+                }
+            },
+        );
+
+        // Two fingers twisting and spreading around a point that stays still
+        let center = Vec2::new(150.0, 100.0);
+        let move_fingers = |app: &mut App, phase: TouchPhase, angle: f32, spread: f32| {
+            let offset = Vec2::from_angle(angle) * spread;
+            for (id, position) in [center - offset, center + offset].into_iter().enumerate() {
+                app.world_mut().write_message(TouchInput {
+                    phase,
+                    position,
+                    window: Entity::PLACEHOLDER,
+                    force: None,
+                    id: id as u64,
+                });
+            }
+            app.update();
+        };
+
+        move_fingers(&mut app, TouchPhase::Started, 0.0, 50.0);
+        move_fingers(&mut app, TouchPhase::Moved, 0.1, 50.0);
+        move_fingers(&mut app, TouchPhase::Moved, 0.25, 54.0);
+        move_fingers(&mut app, TouchPhase::Moved, 0.35, 56.0);
+
+        let gestures = &app.world().resource::<Gestures>().0;
+        assert_eq!(gestures.len(), 4);
+        assert_eq!(gestures[1], (false, None, None));
+
+        // Both start once past their thresholds, and only with the movement after that
+        let (dragging, scale, rotation) = gestures[2];
+        assert!(!dragging);
+        assert!((scale.unwrap() - 1.08).abs() < 1e-4);
+        assert!((rotation.unwrap() - 0.15).abs() < 1e-4);
+
+        let (dragging, scale, rotation) = gestures[3];
+        assert!(!dragging);
+        assert!((scale.unwrap() - 112.0 / 108.0).abs() < 1e-4);
+        assert!((rotation.unwrap() - 0.1).abs() < 1e-4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_settings_load_from_ron() {
+        let settings: TouchInputSettings =
+            ron::from_str("(pan_threshold: 8.0, pixel_scale: 2.0)").unwrap();
+        let expected = TouchInputSettings::default()
+            .with_pan_threshold(8.0)
+            .with_pixel_scale(2.0);
+
+        assert_eq!(settings.pan_threshold, expected.pan_threshold);
+        assert_eq!(settings.pixels(settings.pan_threshold), 16.0);
+        assert_eq!(settings.tap_time, expected.tap_time);
     }
 }
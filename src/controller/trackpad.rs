@@ -0,0 +1,335 @@
+use bevy_app::prelude::*;
+use bevy_camera::{Camera, Projection};
+use bevy_ecs::{prelude::*, query::QueryData, system::SystemParam};
+use bevy_input::{
+    gestures::{PanGesture, PinchGesture, RotationGesture},
+    mouse::{MouseScrollUnit, MouseWheel},
+};
+use bevy_math::Vec2;
+use bevy_picking::pointer::PointerId;
+use bevy_transform::components::GlobalTransform;
+
+use super::{
+    CameraController, ControlMessage, ControllerSettings, Ground, PointerCameras, TrackpadMode,
+    screen_ground_point, view_aligned_translation,
+};
+use crate::{
+    CameraChange, LookTransform,
+    inputs::{Binding, Inputs},
+};
+
+pub(super) struct TrackpadController;
+
+impl Plugin for TrackpadController {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, trackpad_control.in_set(CameraChange::Before));
+    }
+}
+
+#[derive(SystemParam)]
+pub(super) struct TrackpadGestures<'w, 's> {
+    inputs: Inputs<'w, 's>,
+    scroll: MessageReader<'w, 's, MouseWheel>,
+    pan: MessageReader<'w, 's, PanGesture>,
+    pinch: MessageReader<'w, 's, PinchGesture>,
+    rotation: MessageReader<'w, 's, RotationGesture>,
+}
+
+impl TrackpadGestures<'_, '_> {
+    /// How far two fingers scrolled since the last read, in logical pixels, skipping the scrolls
+    /// that zoom in `trackpad` mode
+    fn scroll(&mut self, trackpad: TrackpadMode, pixels_per_line: f32) -> Vec2 {
+        self.scroll
+            .read()
+            .filter(|ev| trackpad.scroll_pans(ev.unit))
+            .map(|ev| match ev.unit {
+                MouseScrollUnit::Line => Vec2::new(ev.x, ev.y) * pixels_per_line,
+                MouseScrollUnit::Pixel => Vec2::new(ev.x, ev.y),
+            })
+            .sum()
+    }
+
+    /// How far two fingers moved with pan gestures since the last read, in logical pixels
+    fn pan(&mut self) -> Vec2 {
+        self.pan.read().map(|ev| ev.0).sum()
+    }
+
+    /// How much closer a pinch zooms since the last read, `1.0` if there was no pinch
+    fn pinch_scalar(&mut self) -> f32 {
+        (-self.pinch.read().map(|ev| ev.0).sum::<f32>()).exp()
+    }
+
+    /// Windows and Linux send a pinch as scrolling with Ctrl held, the default of `bindings`
+    fn scroll_pinches(&self, bindings: &[Binding]) -> bool {
+        self.inputs.pressed_binding(bindings).is_some()
+    }
+
+    /// How far two fingers twisted counterclockwise since the last read, in radians
+    fn rotation(&mut self) -> f32 {
+        // The rotation gesture is in degrees
+        self.rotation
+            .read()
+            .map(|ev| ev.0)
+            .sum::<f32>()
+            .to_radians()
+    }
+
+    fn clear(&mut self) {
+        self.scroll.clear();
+        self.pan.clear();
+        self.pinch.clear();
+        self.rotation.clear();
+    }
+}
+
+#[derive(QueryData)]
+pub(super) struct TrackpadCamera {
+    camera: &'static Camera,
+    transform: &'static GlobalTransform,
+    look_transform: &'static LookTransform,
+    controller: &'static CameraController,
+    projection: Option<&'static Projection>,
+    ground: Option<&'static Ground>,
+}
+
+/// Pans with two finger scrolling, zooms towards the cursor with a pinch and rotates with a twist
+fn trackpad_control(
+    cam_q: Query<TrackpadCamera>,
+    settings: ControllerSettings,
+    pointer_cameras: PointerCameras,
+    mut gestures: TrackpadGestures,
+    mut camera_writer: MessageWriter<ControlMessage>,
+) {
+    let Some((camera_entity, camera)) = pointer_cameras
+        .under_cursor()
+        .and_then(|entity| cam_q.get(entity).ok().map(|camera| (entity, camera)))
+    else {
+        gestures.clear();
+        return;
+    };
+
+    let settings = settings.get(camera_entity);
+    if settings.trackpad == TrackpadMode::Off {
+        gestures.clear();
+        return;
+    }
+
+    let pixels_per_line = camera.controller.pixels_per_line;
+    let scrolled = gestures.scroll(settings.trackpad, pixels_per_line);
+    let (scrolled, scroll_zoom_scalar) =
+        if gestures.scroll_pinches(&settings.buttons.trackpad_scroll_zoom) {
+            let lines = scrolled.y / pixels_per_line;
+            (
+                Vec2::ZERO,
+                (-lines * settings.mouse_zoom_sensitivity_modifier).exp(),
+            )
+        } else {
+            (scrolled, 1.0)
+        };
+
+    // Move the content with the fingers
+    let pan = scrolled + gestures.pan();
+    if let Some(viewport) = camera.camera.logical_viewport_size() {
+        if pan != Vec2::ZERO && viewport.y > 0.0 {
+            camera_writer.write(ControlMessage::TranslateTarget(
+                camera_entity,
+                view_aligned_translation(
+                    camera.look_transform,
+                    camera.projection,
+                    Vec2::new(-pan.x, pan.y) / viewport.y,
+                ),
+            ));
+        }
+    }
+
+    // Twisting the content counterclockwise turns the camera clockwise
+    let rotation = gestures.rotation();
+    if rotation != 0.0 {
+        camera_writer.write(ControlMessage::Orbit(
+            camera_entity,
+            Vec2::new(rotation, 0.0),
+        ));
+    }
+
+    let zoom_scalar = gestures.pinch_scalar() * scroll_zoom_scalar;
+    if zoom_scalar != 1.0 {
+        let picked = settings
+            .anchor_on_picking_hits
+            .then(|| pointer_cameras.picking_hit(PointerId::Mouse, camera_entity))
+            .flatten();
+        let zoom_target = picked
+            .or_else(|| {
+                let window = pointer_cameras.window()?;
+                screen_ground_point(
+                    window.cursor_position()?,
+                    camera.camera,
+                    camera.transform,
+                    window,
+                    camera.controller,
+                    camera.ground,
+                )
+            })
+            .unwrap_or(camera.look_transform.target);
+
+        camera_writer.write(ControlMessage::Zoom {
+            camera: camera_entity,
+            zoom_scalar,
+            zoom_target,
+        });
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_camera::PerspectiveProjection;
+    use bevy_input::keyboard::KeyCode;
+    use bevy_math::{Ray3d, Vec3, primitives::InfinitePlane3d};
+
+    use crate::{
+        look_angles::LookAngles,
+        test_utils::{MapCameraTestApp, WINDOW_HEIGHT, WINDOW_WIDTH},
+    };
+
+    const CENTER: Vec2 = Vec2::new(WINDOW_WIDTH as f32 / 2.0, WINDOW_HEIGHT as f32 / 2.0);
+
+    fn trackpad_app(trackpad: TrackpadMode) -> MapCameraTestApp {
+        let mut harness = MapCameraTestApp::new();
+        harness.settings_mut().trackpad = trackpad;
+        harness
+    }
+
+    fn ground_point(ray: Ray3d) -> Vec3 {
+        let distance = ray
+            .intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))
+            .unwrap();
+        ray.get_point(distance)
+    }
+
+    fn look_angles(look_transform: &LookTransform) -> LookAngles {
+        LookAngles::from_vector(-look_transform.look_direction().unwrap())
+    }
+
+    #[test]
+    fn test_scroll_pans_with_the_fingers() {
+        let start = trackpad_app(TrackpadMode::On).look_transform();
+        let fov = PerspectiveProjection::default().fov;
+        let view_height = 2.0 * start.radius() * (fov / 2.0).tan();
+
+        for (unit, scroll, pixels) in [
+            (MouseScrollUnit::Pixel, 60.0, 60.0),
+            (MouseScrollUnit::Line, 1.0, 53.0),
+        ] {
+            let mut harness = trackpad_app(TrackpadMode::On);
+            harness.move_cursor(CENTER);
+            harness.scroll(unit, Vec2::new(0.0, scroll));
+            harness.step();
+
+            // Scrolling up moves the camera forward, by as much of the view height as was scrolled
+            let panned = harness.look_transform();
+            let expected = Vec3::new(0.0, 0.0, -pixels / WINDOW_HEIGHT as f32 * view_height);
+            assert!(
+                panned.target.abs_diff_eq(expected, 1e-3),
+                "{unit:?} panned to {}",
+                panned.target
+            );
+            assert!((panned.radius() - start.radius()).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_pinch_zooms_towards_cursor() {
+        let cursor = CENTER + Vec2::new(150.0, -100.0);
+
+        for ctrl_scroll in [false, true] {
+            let mut harness = trackpad_app(TrackpadMode::Detect);
+            let start = harness.look_transform();
+            let start_angles = look_angles(&start);
+            let under_cursor = ground_point(harness.ray(cursor).unwrap());
+
+            harness.move_cursor(cursor);
+            if ctrl_scroll {
+                harness.press(KeyCode::ControlLeft);
+                harness.step();
+                harness.scroll(MouseScrollUnit::Pixel, Vec2::new(0.0, 100.0));
+            } else {
+                harness.app.world_mut().write_message(PinchGesture(0.2));
+            }
+            harness.step();
+
+            let zoomed = harness.look_transform();
+            let zoomed_angles = look_angles(&zoomed);
+            assert!(zoomed.radius() < start.radius() * 0.95, "{ctrl_scroll}");
+            assert!((zoomed_angles.get_pitch() - start_angles.get_pitch()).abs() < 1e-4);
+            assert!(
+                ground_point(harness.ray(cursor).unwrap()).abs_diff_eq(under_cursor, 1e-2),
+                "{ctrl_scroll}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scroll_zoom_modifier_is_rebindable() {
+        let mut harness = trackpad_app(TrackpadMode::Detect);
+        harness.settings_mut().buttons.trackpad_scroll_zoom =
+            vec![Binding::at_least([KeyCode::AltLeft])];
+        let start = harness.look_transform();
+
+        harness.move_cursor(CENTER);
+        harness.press(KeyCode::AltLeft);
+        harness.step();
+        harness.scroll(MouseScrollUnit::Pixel, Vec2::new(0.0, 100.0));
+        harness.step();
+
+        let zoomed = harness.look_transform();
+        assert!(zoomed.radius() < start.radius() * 0.95);
+        assert!(zoomed.target.abs_diff_eq(start.target, 1e-4));
+    }
+
+    #[test]
+    fn test_ctrl_wheel_still_tilts() {
+        let mut harness = trackpad_app(TrackpadMode::Detect);
+        let start = harness.look_transform();
+
+        harness.move_cursor(CENTER);
+        harness.press(KeyCode::ControlLeft);
+        harness.step();
+        harness.scroll(MouseScrollUnit::Line, Vec2::Y);
+        harness.step();
+
+        let tilted = harness.look_transform();
+        assert!(look_angles(&tilted).get_pitch() < look_angles(&start).get_pitch() - 0.01);
+        assert!((tilted.radius() - start.radius()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_twist_rotates_the_other_way() {
+        let mut harness = trackpad_app(TrackpadMode::On);
+        let start = look_angles(&harness.look_transform());
+
+        harness.move_cursor(CENTER);
+        harness.app.world_mut().write_message(RotationGesture(10.0));
+        harness.step();
+
+        // Twisting the content counterclockwise lowers the yaw
+        let rotated = look_angles(&harness.look_transform());
+        assert!((rotated.get_yaw() - start.get_yaw() + 10f32.to_radians()).abs() < 1e-4);
+        assert!((rotated.get_pitch() - start.get_pitch()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_detect_pans_with_pixel_scrolling() {
+        assert!(!TrackpadMode::Off.scroll_pans(MouseScrollUnit::Pixel));
+        assert!(TrackpadMode::On.scroll_pans(MouseScrollUnit::Line));
+        assert!(TrackpadMode::Detect.scroll_pans(MouseScrollUnit::Pixel));
+        assert!(!TrackpadMode::Detect.scroll_pans(MouseScrollUnit::Line));
+    }
+}
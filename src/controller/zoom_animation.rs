@@ -0,0 +1,101 @@
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_reflect::prelude::*;
+
+use super::ControlMessage;
+
+/// Zooms a camera towards a point over time, removed from the camera when done.
+///
+/// The zoom is applied like a [`ControlMessage::Zoom`], so it keeps `zoom_target` in the same place on
+/// screen and respects the zoom limits just like zooming with the scroll wheel.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Debug)]
+pub struct ZoomAnimation {
+    /// The total zoom, the radius or orthographic scale is multiplied by this
+    pub zoom_scalar: f32,
+    pub zoom_target: Vec3,
+    /// In seconds
+    pub duration: f32,
+    elapsed: f32,
+}
+
+impl ZoomAnimation {
+    pub fn new(zoom_scalar: f32, zoom_target: Vec3, duration: f32) -> Self {
+        Self {
+            zoom_scalar,
+            zoom_target,
+            duration,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances the animation and returns the part of the zoom for this frame
+    pub(super) fn advance(&mut self, camera: Entity, delta_seconds: f32) -> Option<ControlMessage> {
+        let previous_progress = self.progress(self.elapsed);
+        self.elapsed += delta_seconds;
+        let progress = self.progress(self.elapsed);
+
+        (progress > previous_progress).then(|| ControlMessage::Zoom {
+            camera,
+            zoom_scalar: self.zoom_scalar.powf(progress - previous_progress),
+            zoom_target: self.zoom_target,
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// How far into the zoom the animation is at `elapsed` seconds, eased in and out
+    fn progress(&self, elapsed: f32) -> f32 {
+        let t = (elapsed / self.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_app::prelude::*;
+    use core::time::Duration;
+
+    use crate::{CameraController, LookTransform, controller::tests::control_app};
+
+    #[test]
+    fn test_zoom_animation_reaches_zoom() {
+        let mut app = control_app(Duration::from_secs_f64(1.0 / 60.0));
+
+        let look_transform = LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y);
+        let zoom_target = Vec3::new(4.0, 0.0, 0.0);
+        let camera = app
+            .world_mut()
+            .spawn((
+                look_transform,
+                CameraController::default(),
+                ZoomAnimation::new(0.5, zoom_target, 0.25),
+            ))
+            .id();
+
+        let radius = |app: &App| app.world().get::<LookTransform>(camera).unwrap().radius();
+
+        app.update();
+        app.update();
+        assert!(radius(&app) < look_transform.radius());
+        assert!(radius(&app) > look_transform.radius() * 0.5);
+
+        for _ in 0..30 {
+            app.update();
+        }
+
+        assert!(app.world().get::<ZoomAnimation>(camera).is_none());
+        assert!((radius(&app) - look_transform.radius() * 0.5).abs() < 1e-3);
+    }
+}
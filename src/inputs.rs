@@ -13,31 +13,55 @@ pub(crate) struct Inputs<'w, 's> {
 
 impl<'w, 's> Inputs<'w, 's> {
     /// Returns true if only the buttons in `input` are pressed.
-    pub fn multi_pressed(&self, input: &Vec<InputButton>) -> bool {
+    pub fn multi_pressed(&self, input: &[InputButton]) -> bool {
         if input.is_empty() {
             return false;
         }
 
         let currently_pressed = self.keys.get_pressed().count() + self.mouse.get_pressed().count();
 
-        if currently_pressed != input.len() {
-            return false;
-        }
-
-        let mut pressed = true;
+        currently_pressed == input.len() && self.all_pressed(input)
+    }
 
-        for button in input {
-            pressed &= match button {
-                InputButton::Mouse(mouse) => self.mouse.pressed(*mouse),
-                InputButton::Key(key) => self.keys.pressed(*key),
+    /// Returns true if the buttons of `binding` are pressed the way its [`BindingMode`] requires
+    pub fn binding_pressed(&self, binding: &Binding) -> bool {
+        match binding.mode {
+            BindingMode::Exact => self.multi_pressed(&binding.buttons),
+            BindingMode::AtLeast => {
+                !binding.buttons.is_empty() && self.all_pressed(&binding.buttons)
             }
         }
+    }
+
+    /// Returns true if any of the `bindings` became pressed this frame
+    pub fn binding_just_pressed(&self, bindings: &[Binding]) -> bool {
+        bindings.iter().any(|binding| {
+            self.binding_pressed(binding)
+                && binding.buttons.iter().any(|button| match button {
+                    InputButton::Mouse(mouse) => self.mouse.just_pressed(*mouse),
+                    InputButton::Key(key) => self.keys.just_pressed(*key),
+                })
+        })
+    }
 
-        pressed
+    /// The most specific pressed binding of `bindings`, the one with the most buttons
+    pub fn pressed_binding<'a>(&self, bindings: &'a [Binding]) -> Option<&'a Binding> {
+        bindings
+            .iter()
+            .filter(|binding| self.binding_pressed(binding))
+            .max_by_key(|binding| binding.buttons.len())
+    }
+
+    fn all_pressed(&self, input: &[InputButton]) -> bool {
+        input.iter().all(|button| match button {
+            InputButton::Mouse(mouse) => self.mouse.pressed(*mouse),
+            InputButton::Key(key) => self.keys.pressed(*key),
+        })
     }
 }
 
 #[derive(Hash, Debug, Clone, Copy, Reflect, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InputButton {
     Mouse(MouseButton),
     Key(KeyCode),
@@ -52,3 +76,100 @@ impl From<KeyCode> for InputButton {
         Self::Key(value)
     }
 }
+
+/// How the buttons of a [`Binding`] need to be pressed
+#[derive(Debug, Default, Clone, Copy, Reflect, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BindingMode {
+    /// Only these buttons, holding any other button releases the binding
+    Exact,
+    /// At least these buttons, other buttons can be held too
+    #[default]
+    AtLeast,
+}
+
+/// A combination of buttons that triggers an action
+#[derive(Debug, Clone, Reflect, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Binding {
+    pub buttons: Vec<InputButton>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: BindingMode,
+}
+
+impl Binding {
+    /// Pressed when only `buttons` are pressed
+    pub fn exact(buttons: impl IntoIterator<Item = impl Into<InputButton>>) -> Self {
+        Self {
+            buttons: buttons.into_iter().map(Into::into).collect(),
+            mode: BindingMode::Exact,
+        }
+    }
+
+    /// Pressed when at least `buttons` are pressed
+    pub fn at_least(buttons: impl IntoIterator<Item = impl Into<InputButton>>) -> Self {
+        Self {
+            buttons: buttons.into_iter().map(Into::into).collect(),
+            mode: BindingMode::AtLeast,
+        }
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+
+    #[test]
+    fn test_exact_and_at_least_bindings() {
+        let mut world = World::new();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::ControlLeft);
+        world.insert_resource(keys);
+        let mut mouse = ButtonInput::<MouseButton>::default();
+        mouse.press(MouseButton::Left);
+        world.insert_resource(mouse);
+
+        let bindings = [
+            Binding::exact([MouseButton::Left]),
+            Binding::at_least([MouseButton::Left]),
+            Binding::exact([
+                InputButton::Mouse(MouseButton::Left),
+                InputButton::Key(KeyCode::ControlLeft),
+            ]),
+            Binding::at_least([MouseButton::Right]),
+        ];
+
+        let (pressed, most_specific, just_pressed) = world
+            .run_system_once(move |inputs: Inputs| {
+                (
+                    bindings
+                        .iter()
+                        .map(|binding| inputs.binding_pressed(binding))
+                        .collect::<Vec<_>>(),
+                    inputs.pressed_binding(&bindings).cloned(),
+                    inputs.binding_just_pressed(&bindings[3..]),
+                )
+            })
+            .unwrap();
+
+        // Control is held, so only pressing the left button isn't exact
+        assert_eq!(pressed, [false, true, true, false]);
+        assert_eq!(
+            most_specific,
+            Some(Binding::exact([
+                InputButton::Mouse(MouseButton::Left),
+                InputButton::Key(KeyCode::ControlLeft),
+            ]))
+        );
+        assert!(!just_pressed);
+    }
+}
@@ -4,16 +4,20 @@ pub mod controller;
 pub mod inputs;
 pub mod look_angles;
 pub mod look_transform;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 use bevy_app::prelude::*;
 use bevy_camera::{Camera, Camera3d};
 use bevy_ecs::prelude::*;
 use bevy_input::InputSystems;
+use bevy_picking::PickingSystems;
+use bevy_time::Time;
 use bevy_transform::components::Transform;
 
 // re-exports
-pub use controller::{CameraController, CameraControllerSettings};
-pub use look_transform::LookTransform;
+pub use controller::{CameraController, CameraControllerSettings, PanBounds};
+pub use look_transform::{LookTransform, Smoother};
 
 /// Orbital camera plugin
 #[derive(Clone, Copy)]
@@ -25,9 +29,12 @@ impl Plugin for MapCameraPlugin {
             PreUpdate,
             (CameraChange::Before, CameraChange::After)
                 .chain()
-                .after(InputSystems),
+                .after(InputSystems)
+                .after(PickingSystems::Hover),
         );
 
+        app.register_type::<Smoother>();
+
         app.add_systems(
             PreUpdate,
             look_transform_system
@@ -50,15 +57,38 @@ impl Plugin for MapCameraPlugin {
     }
 }
 
-fn look_transform_system(mut lts: Query<(&LookTransform, &mut Transform)>) {
-    lts.iter_mut()
-        .for_each(|(&look_transform, mut scene_transform)| {
-            *scene_transform = look_transform.into();
-        });
+fn look_transform_system(
+    mut lts: Query<(
+        &LookTransform,
+        &mut Transform,
+        Option<&CameraController>,
+        Option<&mut Smoother>,
+    )>,
+    time: Res<Time>,
+) {
+    let delta_seconds = time.delta_secs();
+
+    for (look_transform, mut scene_transform, controller, smoother) in lts.iter_mut() {
+        let smoothing_weight = controller.map_or(0.0, |controller| controller.smoothing_weight);
+
+        match smoother {
+            Some(mut smoother) if smoothing_weight > 0.0 => {
+                *scene_transform = smoother
+                    .smooth_transform(look_transform, smoothing_weight, delta_seconds)
+                    .into();
+            }
+            smoother => {
+                if let Some(mut smoother) = smoother {
+                    smoother.reset();
+                }
+                *scene_transform = (*look_transform).into();
+            }
+        }
+    }
 }
 
 #[derive(Component)]
-#[require(CameraController, Camera3d, LookTransform, Camera = default_camera())]
+#[require(CameraController, Camera3d, LookTransform, Smoother, Camera = default_camera())]
 pub struct MapCamera;
 
 fn default_camera() -> Camera {
@@ -76,3 +106,152 @@ pub enum CameraChange {
     /// Systems that should run after any changes to the camera transform are made
     After,
 }
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_math::Vec3;
+    use core::time::Duration;
+
+    use crate::test_utils::headless_app;
+
+    fn smoothing_app(fps: u32) -> App {
+        let mut app = headless_app(Duration::from_secs_f64(1.0 / fps as f64));
+        app.add_systems(PreUpdate, look_transform_system);
+        app
+    }
+
+    fn spawn_camera(app: &mut App, smoothing_weight: f32) -> Entity {
+        app.world_mut()
+            .spawn((
+                LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y),
+                CameraController {
+                    smoothing_weight,
+                    ..Default::default()
+                },
+                Smoother::default(),
+            ))
+            .id()
+    }
+
+    /// Moves the camera to a new [`LookTransform`] and runs `seconds` worth of frames.
+    fn move_camera(app: &mut App, camera: Entity, fps: u32, seconds: f32) -> Transform {
+        // Let the smoother pick up the start position
+        app.update();
+
+        *app.world_mut().get_mut::<LookTransform>(camera).unwrap() = LookTransform::new(
+            Vec3::new(20.0, 5.0, 0.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::Y,
+        );
+
+        for _ in 0..(fps as f32 * seconds).round() as u32 {
+            app.update();
+        }
+
+        *app.world().get::<Transform>(camera).unwrap()
+    }
+
+    #[test]
+    fn test_smoothing_is_frame_rate_independent() {
+        let mut app_60 = smoothing_app(60);
+        let camera_60 = spawn_camera(&mut app_60, 0.8);
+        let transform_60 = move_camera(&mut app_60, camera_60, 60, 0.1);
+
+        let mut app_30 = smoothing_app(30);
+        let camera_30 = spawn_camera(&mut app_30, 0.8);
+        let transform_30 = move_camera(&mut app_30, camera_30, 30, 0.1);
+
+        let mut app_144 = smoothing_app(144);
+        let camera_144 = spawn_camera(&mut app_144, 0.8);
+        let transform_144 = move_camera(&mut app_144, camera_144, 144, 0.125);
+
+        let mut app_24 = smoothing_app(24);
+        let camera_24 = spawn_camera(&mut app_24, 0.8);
+        let transform_24 = move_camera(&mut app_24, camera_24, 24, 0.125);
+
+        // Still on the way to the new LookTransform
+        let end: Transform = LookTransform::new(
+            Vec3::new(20.0, 5.0, 0.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::Y,
+        )
+        .into();
+        assert!(transform_60.translation.distance(end.translation) > 0.1);
+
+        assert!(
+            transform_60
+                .translation
+                .abs_diff_eq(transform_30.translation, 1e-4)
+        );
+        assert!(
+            transform_60
+                .rotation
+                .abs_diff_eq(transform_30.rotation, 1e-4)
+        );
+        assert!(
+            transform_144
+                .translation
+                .abs_diff_eq(transform_24.translation, 1e-4)
+        );
+        assert!(
+            transform_144
+                .rotation
+                .abs_diff_eq(transform_24.rotation, 1e-4)
+        );
+    }
+
+    #[test]
+    fn test_smoothing_reaches_look_transform() {
+        let mut app = smoothing_app(60);
+        let camera = spawn_camera(&mut app, 0.8);
+        let transform = move_camera(&mut app, camera, 60, 2.0);
+
+        let end: Transform = LookTransform::new(
+            Vec3::new(20.0, 5.0, 0.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::Y,
+        )
+        .into();
+
+        assert!(transform.translation.abs_diff_eq(end.translation, 1e-4));
+    }
+
+    #[test]
+    fn test_smoothing_disabled() {
+        let mut app = smoothing_app(60);
+        let camera = spawn_camera(&mut app, 0.0);
+        let transform = move_camera(&mut app, camera, 60, 1.0 / 60.0);
+
+        let end: Transform = LookTransform::new(
+            Vec3::new(20.0, 5.0, 0.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::Y,
+        )
+        .into();
+
+        assert_eq!(transform, end);
+    }
+
+    #[test]
+    fn test_smoothing_weight_is_clamped() {
+        let mut app = smoothing_app(60);
+        let camera = spawn_camera(&mut app, 1.5);
+        let transform = move_camera(&mut app, camera, 60, 1.0);
+
+        let start: Transform =
+            LookTransform::new(Vec3::new(0.0, 10.0, 10.0), Vec3::ZERO, Vec3::Y).into();
+
+        // Still moving, just very slowly
+        assert!(transform.translation.is_finite());
+        assert!(transform.translation != start.translation);
+    }
+}
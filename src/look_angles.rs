@@ -12,6 +12,9 @@ pub struct LookAngles {
 }
 
 impl LookAngles {
+    /// The largest pitch in either direction, things can get weird if we are parallel to the UP vector.
+    pub const MAX_PITCH: f32 = PI / 2.0 - 0.01;
+
     pub fn from_vector(v: Vec3) -> Self {
         let mut p = Self::default();
         p.set_direction(v);
@@ -42,9 +45,7 @@ impl LookAngles {
     }
 
     pub fn set_pitch(&mut self, pitch: f32) {
-        // Things can get weird if we are parallel to the UP vector.
-        let up_eps = 0.01;
-        self.pitch = pitch.min(PI / 2.0 - up_eps).max(-PI / 2.0 + up_eps);
+        self.pitch = pitch.clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
     }
 
     pub fn get_pitch(&self) -> f32 {
@@ -55,6 +56,15 @@ impl LookAngles {
         self.set_pitch(self.get_pitch() + delta);
     }
 
+    /// Clamps the yaw to the range `minimum..=maximum`, taking the wrap around at a full turn into account.
+    pub fn clamp_yaw(&mut self, minimum: f32, maximum: f32) {
+        // Move the yaw to the full turn centered on the range before clamping it
+        let center = (minimum + maximum) / 2.0;
+        let yaw = center + (self.yaw - center + PI).rem_euclid(2.0 * PI) - PI;
+
+        self.set_yaw(yaw.min(maximum).max(minimum));
+    }
+
     pub fn assert_not_looking_up(&self) {
         let is_looking_up = relative_eq!(self.unit_vector().dot(Vec3::Y).abs(), 1.0);
 
@@ -150,6 +160,29 @@ mod tests {
         assert_relative_eq!(pitch, -PI / 2.0);
     }
 
+    #[test]
+    fn test_clamp_yaw() {
+        let mut angles = LookAngles::default();
+
+        angles.set_yaw(0.5);
+        angles.clamp_yaw(-1.0, 1.0);
+        assert_relative_eq!(angles.get_yaw(), 0.5);
+
+        angles.set_yaw(1.5);
+        angles.clamp_yaw(-1.0, 1.0);
+        assert_relative_eq!(angles.get_yaw(), 1.0);
+
+        // Closer to the minimum when wrapping around
+        angles.set_yaw(-1.5 + 2.0 * PI);
+        angles.clamp_yaw(-1.0, 1.0);
+        assert_relative_eq!(angles.get_yaw(), -1.0);
+
+        // Range crossing a full turn
+        angles.set_yaw(-3.0);
+        angles.clamp_yaw(3.0, 4.0);
+        assert_relative_eq!(angles.get_yaw(), 2.0 * PI - 3.0, epsilon = 1e-5);
+    }
+
     #[test]
     fn test_yaw_and_pitch() {
         let (yaw, pitch) = yaw_and_pitch_from_vector(Vec3::new(0.5f32.sqrt(), 1.0, 0.5f32.sqrt()));
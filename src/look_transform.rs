@@ -50,6 +50,87 @@ impl LookTransform {
     }
 }
 
+/// The frame rate that [`Smoother::smooth_transform`]'s `lag_weight` is defined at.
+const REFERENCE_FPS: f32 = 60.0;
+
+/// The highest `lag_weight` [`Smoother::smooth_transform`] uses, `1.0` would never move.
+const MAX_LAG_WEIGHT: f32 = 0.999;
+
+/// Keeps the smoothed state of a [`LookTransform`] between frames.
+///
+/// The smoothed transform eases towards the [`LookTransform`] every frame, and is what ends up in the
+/// camera's `Transform`.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default, Debug)]
+pub struct Smoother {
+    lerp_tfm: Option<LookTransform>,
+}
+
+impl Smoother {
+    /// Moves the smoothed transform towards `new_tfm` and returns it.
+    ///
+    /// `lag_weight` is the fraction of the remaining distance that is kept after 1/60th of a second,
+    /// scaling it with `delta_seconds` keeps the movement the same at any frame rate. It is clamped to
+    /// `0.0..1.0` so the transform always reaches `new_tfm`.
+    pub fn smooth_transform(
+        &mut self,
+        new_tfm: &LookTransform,
+        lag_weight: f32,
+        delta_seconds: f32,
+    ) -> LookTransform {
+        let lag_weight = lag_weight.clamp(0.0, MAX_LAG_WEIGHT);
+
+        let old_lerp_tfm = self.lerp_tfm.unwrap_or(*new_tfm);
+
+        let lead_weight = 1.0 - lag_weight.powf(delta_seconds * REFERENCE_FPS);
+
+        // Interpolate the target, radius and direction separately so that the eye orbits
+        // around the target instead of cutting through it.
+        let target = old_lerp_tfm.target.lerp(new_tfm.target, lead_weight);
+        let radius = old_lerp_tfm.radius().lerp(new_tfm.radius(), lead_weight);
+        let direction = match (
+            (old_lerp_tfm.eye - old_lerp_tfm.target).try_normalize(),
+            (new_tfm.eye - new_tfm.target).try_normalize(),
+        ) {
+            (Some(old_dir), Some(new_dir)) => old_dir.slerp(new_dir, lead_weight),
+            _ => {
+                self.lerp_tfm = Some(*new_tfm);
+                return *new_tfm;
+            }
+        };
+
+        let lerp_tfm = LookTransform {
+            eye: target + direction * radius,
+            target,
+            up: new_tfm.up,
+        };
+
+        self.lerp_tfm = Some(lerp_tfm);
+
+        lerp_tfm
+    }
+
+    /// Moves the smoothed transform by `delta` without any smoothing.
+    ///
+    /// Used for movements that need to stay pixel exact, like grab panning.
+    pub fn translate(&mut self, delta: Vec3) {
+        if let Some(lerp_tfm) = &mut self.lerp_tfm {
+            lerp_tfm.eye += delta;
+            lerp_tfm.target += delta;
+        }
+    }
+
+    /// The smoothed transform of the last call to [`Self::smooth_transform`]
+    pub fn smoothed(&self) -> Option<LookTransform> {
+        self.lerp_tfm
+    }
+
+    /// Forgets the smoothed state, the next call to [`Self::smooth_transform`] will snap to its input.
+    pub fn reset(&mut self) {
+        self.lerp_tfm = None;
+    }
+}
+
 fn eye_look_at_target_transform(eye: Vec3, target: Vec3, up: Vec3) -> Transform {
     // If eye and target are very close, we avoid imprecision issues by keeping the look vector a unit vector.
     let look_vector = (target - eye).normalize();
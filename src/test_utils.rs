@@ -0,0 +1,415 @@
+//! Runs [`MapCameraPlugin`] in an [`App`] without a window or a GPU, so that the camera controls can be
+//! tested on CI.
+//!
+//! Enabled with the `test-utils` feature.
+//!
+//! ```ignore
+//! let mut harness = MapCameraTestApp::new();
+//! harness.move_cursor(Vec2::new(400.0, 300.0));
+//! harness.scroll(MouseScrollUnit::Line, Vec2::Y);
+//! harness.step();
+//! assert!(harness.look_transform().radius() < 20.0);
+//! ```
+
+use core::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_camera::{Camera, Projection, RenderTargetInfo};
+use bevy_ecs::prelude::*;
+use bevy_input::{
+    ButtonState, InputPlugin, InputSystems,
+    keyboard::{Key, KeyboardInput, NativeKey},
+    mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+    touch::{TouchInput, TouchPhase},
+};
+use bevy_math::{Ray3d, Vec2, Vec3};
+use bevy_picking::{
+    backend::ray::{RayId, RayMap},
+    pointer::PointerId,
+};
+use bevy_time::{TimePlugin, TimeUpdateStrategy};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_window::{PrimaryWindow, Window, WindowResolution};
+
+use crate::{
+    CameraChange, CameraController, CameraControllerSettings, LookTransform, MapCamera,
+    MapCameraPlugin, inputs::InputButton,
+};
+
+/// Width of the test window, in logical pixels
+pub const WINDOW_WIDTH: u32 = 800;
+/// Height of the test window, in logical pixels
+pub const WINDOW_HEIGHT: u32 = 600;
+
+/// Where the pointer rays of the [`RayMap`] come from
+#[derive(Resource)]
+struct PointerRays {
+    /// Cast from the cursor and the touches every frame, like a picking backend, until a ray is
+    /// inserted by hand
+    automatic: bool,
+}
+
+/// An [`App`] without a window or a GPU whose time moves `frame_time` forward every update
+pub fn headless_app(frame_time: Duration) -> App {
+    let mut app = App::new();
+    app.add_plugins(TimePlugin);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+    app
+}
+
+/// An [`App`] with [`MapCameraPlugin`], a primary window and one [`MapCamera`].
+///
+/// Frames are 1/60th of a second apart. The camera isn't smoothed, so its `Transform` follows the
+/// [`LookTransform`] at once, and its [`CameraControllerSettings`] have no inertia.
+pub struct MapCameraTestApp {
+    pub app: App,
+    pub window: Entity,
+    pub camera: Entity,
+}
+
+impl Default for MapCameraTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MapCameraTestApp {
+    /// The camera looks at the origin from 10 units up and 10 units back
+    pub fn new() -> Self {
+        Self::with_look_transform(LookTransform::new(
+            Vec3::new(0.0, 10.0, 10.0),
+            Vec3::ZERO,
+            Vec3::Y,
+        ))
+    }
+
+    pub fn with_look_transform(look_transform: LookTransform) -> Self {
+        let mut app = headless_app(Duration::from_secs_f64(1.0 / 60.0));
+        app.add_plugins((InputPlugin, MapCameraPlugin));
+        app.init_resource::<RayMap>();
+        app.insert_resource(PointerRays { automatic: true });
+        app.add_systems(
+            PreUpdate,
+            cast_pointer_rays
+                .after(InputSystems)
+                .before(CameraChange::Before),
+        );
+        app.add_systems(PostUpdate, update_cameras);
+
+        let window = app
+            .world_mut()
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(WINDOW_WIDTH, WINDOW_HEIGHT),
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+
+        let mut settings = CameraControllerSettings::default();
+        settings.inertia.enabled = false;
+        let camera = app
+            .world_mut()
+            .spawn((
+                MapCamera,
+                look_transform,
+                CameraController {
+                    smoothing_weight: 0.0,
+                    ..Default::default()
+                },
+                settings,
+            ))
+            .id();
+
+        let mut harness = Self {
+            app,
+            window,
+            camera,
+        };
+        // Let the camera pick up its transform and projection
+        harness.step();
+        harness
+    }
+
+    /// The settings of the camera
+    pub fn settings_mut(&mut self) -> Mut<'_, CameraControllerSettings> {
+        self.app
+            .world_mut()
+            .get_mut::<CameraControllerSettings>(self.camera)
+            .unwrap()
+    }
+
+    pub fn look_transform(&self) -> LookTransform {
+        *self.app.world().get::<LookTransform>(self.camera).unwrap()
+    }
+
+    /// Runs one frame
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn step_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Moves the cursor to `position` in logical pixels, `None` moves it out of the window
+    pub fn move_cursor(&mut self, position: impl Into<Option<Vec2>>) {
+        self.app
+            .world_mut()
+            .get_mut::<Window>(self.window)
+            .unwrap()
+            .set_cursor_position(position.into());
+    }
+
+    pub fn press(&mut self, button: impl Into<InputButton>) {
+        self.button(button.into(), ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, button: impl Into<InputButton>) {
+        self.button(button.into(), ButtonState::Released);
+    }
+
+    fn button(&mut self, button: InputButton, state: ButtonState) {
+        let window = self.window;
+        let world = self.app.world_mut();
+        match button {
+            InputButton::Mouse(button) => {
+                world.write_message(MouseButtonInput {
+                    button,
+                    state,
+                    window,
+                });
+            }
+            InputButton::Key(key_code) => {
+                world.write_message(KeyboardInput {
+                    key_code,
+                    logical_key: Key::Unidentified(NativeKey::Unidentified),
+                    state,
+                    text: None,
+                    repeat: false,
+                    window,
+                });
+            }
+        }
+    }
+
+    /// Moves the mouse by `delta` without moving the cursor, like a drag
+    pub fn mouse_motion(&mut self, delta: Vec2) {
+        self.app.world_mut().write_message(MouseMotion { delta });
+    }
+
+    pub fn scroll(&mut self, unit: MouseScrollUnit, delta: Vec2) {
+        let window = self.window;
+        self.app.world_mut().write_message(MouseWheel {
+            unit,
+            x: delta.x,
+            y: delta.y,
+            window,
+        });
+    }
+
+    /// Starts, moves or lifts the finger `id` at `position` in logical pixels
+    pub fn touch(&mut self, phase: TouchPhase, id: u64, position: Vec2) {
+        let window = self.window;
+        self.app.world_mut().write_message(TouchInput {
+            phase,
+            position,
+            window,
+            force: None,
+            id,
+        });
+    }
+
+    /// Inserts a ray of `pointer` for the camera into the [`RayMap`]. The rays are no longer cast from
+    /// the cursor and touches after this, every ray needs to be inserted by hand.
+    pub fn insert_ray(&mut self, pointer: PointerId, ray: Ray3d) {
+        let camera = self.camera;
+        self.app.world_mut().resource_mut::<PointerRays>().automatic = false;
+        self.app
+            .world_mut()
+            .resource_mut::<RayMap>()
+            .map
+            .insert(RayId::new(camera, pointer), ray);
+    }
+
+    /// Removes all rays inserted with [`Self::insert_ray`]
+    pub fn clear_rays(&mut self) {
+        self.app.world_mut().resource_mut::<RayMap>().map.clear();
+    }
+
+    /// The ray through `position`, in logical pixels, of the camera as it was last frame
+    pub fn ray(&self, position: Vec2) -> Option<Ray3d> {
+        let (camera, transform) = self
+            .app
+            .world()
+            .entity(self.camera)
+            .get_components::<(&Camera, &GlobalTransform)>()?;
+        camera.viewport_to_world(transform, position).ok()
+    }
+}
+
+/// Casts the rays of the cursor and the touches, like the picking backends do
+fn cast_pointer_rays(
+    pointer_rays: Res<PointerRays>,
+    mut ray_map: ResMut<RayMap>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    touches: Res<bevy_input::touch::Touches>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform), With<CameraController>>,
+) {
+    if !pointer_rays.automatic {
+        return;
+    }
+
+    ray_map.map.clear();
+    let Ok(window) = windows.single() else {
+        return;
+    };
+
+    let pointers = window
+        .cursor_position()
+        .map(|position| (PointerId::Mouse, position))
+        .into_iter()
+        .chain(
+            touches
+                .iter()
+                .map(|touch| (PointerId::Touch(touch.id()), touch.position())),
+        );
+
+    for (pointer, position) in pointers {
+        for (entity, camera, transform) in cameras.iter() {
+            if let Ok(ray) = camera.viewport_to_world(transform, position) {
+                ray_map.map.insert(RayId::new(entity, pointer), ray);
+            }
+        }
+    }
+}
+
+/// Does what transform propagation and the render camera system would, the cameras have no parents
+fn update_cameras(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(
+        &Transform,
+        &mut GlobalTransform,
+        &mut Camera,
+        &mut Projection,
+    )>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+
+    for (transform, mut global_transform, mut camera, mut projection) in cameras.iter_mut() {
+        *global_transform = GlobalTransform::from(*transform);
+
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: window.physical_size(),
+            scale_factor: window.scale_factor(),
+        });
+        if let Some(size) = camera.logical_viewport_size() {
+            projection.update(size.x, size.y);
+        }
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+    }
+}
+
+// ████████╗███████╗███████╗████████╗
+// ╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝
+//    ██║   █████╗  ███████╗   ██║
+//    ██║   ██╔══╝  ╚════██║   ██║
+//    ██║   ███████╗███████║   ██║
+//    ╚═╝   ╚══════╝╚══════╝   ╚═╝
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy_input::mouse::MouseButton;
+    use bevy_math::primitives::InfinitePlane3d;
+
+    use crate::look_angles::LookAngles;
+
+    const CENTER: Vec2 = Vec2::new(WINDOW_WIDTH as f32 / 2.0, WINDOW_HEIGHT as f32 / 2.0);
+
+    fn ground_point(ray: Ray3d) -> Vec3 {
+        let distance = ray
+            .intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Y))
+            .unwrap();
+        ray.get_point(distance)
+    }
+
+    fn look_angles(look_transform: &LookTransform) -> LookAngles {
+        LookAngles::from_vector(look_transform.look_direction().unwrap())
+    }
+
+    #[test]
+    fn test_scroll_zooms_towards_cursor() {
+        let mut harness = MapCameraTestApp::new();
+        let start = harness.look_transform();
+
+        harness.move_cursor(CENTER);
+        harness.scroll(MouseScrollUnit::Line, Vec2::Y);
+        harness.step();
+
+        let zoomed = harness.look_transform();
+        assert!(zoomed.radius() < start.radius());
+        assert!(zoomed.target.abs_diff_eq(start.target, 1e-3));
+    }
+
+    #[test]
+    fn test_right_drag_rotates() {
+        let mut harness = MapCameraTestApp::new();
+        let start = look_angles(&harness.look_transform());
+
+        harness.move_cursor(CENTER);
+        harness.press(MouseButton::Right);
+        harness.step();
+        harness.mouse_motion(Vec2::new(100.0, 0.0));
+        harness.step();
+
+        let rotated = look_angles(&harness.look_transform());
+        assert!((rotated.get_yaw() - start.get_yaw()).abs() > 0.1);
+        assert!((rotated.get_pitch() - start.get_pitch()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_left_drag_grabs_the_ground() {
+        let mut harness = MapCameraTestApp::new();
+
+        harness.move_cursor(CENTER);
+        harness.press(MouseButton::Left);
+        harness.step();
+        let grabbed = harness.ray(CENTER).unwrap();
+
+        // Dragging down pulls the ground towards the camera, the target moves forward
+        harness.move_cursor(CENTER + Vec2::Y * 50.0);
+        harness.step_frames(2);
+        let target = harness.look_transform().target;
+        assert!(target.z < -0.1);
+        assert!(target.x.abs() < 1e-3);
+
+        // The grabbed point is under the cursor again
+        let under_cursor = harness.ray(CENTER + Vec2::Y * 50.0).unwrap();
+        assert!(ground_point(grabbed).abs_diff_eq(ground_point(under_cursor), 1e-2));
+    }
+
+    #[test]
+    fn test_one_finger_pans() {
+        let mut harness = MapCameraTestApp::new();
+
+        harness.touch(TouchPhase::Started, 0, CENTER);
+        harness.step();
+        harness.touch(TouchPhase::Moved, 0, CENTER + Vec2::Y * 50.0);
+        harness.step();
+
+        assert!(harness.look_transform().target.z < -0.1);
+
+        harness.touch(TouchPhase::Ended, 0, CENTER + Vec2::Y * 50.0);
+        harness.step();
+        let released = harness.look_transform();
+        harness.step_frames(10);
+        assert_eq!(harness.look_transform(), released);
+    }
+}